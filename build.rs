@@ -0,0 +1,33 @@
+//! Finds the days in `src/dayXX.rs`, so they don't have to be listed by hand.
+//! `framework::days!()` in `src/lib.rs` includes the generated `days.rs`.
+
+use std::{fmt::Write, path::Path};
+
+fn main() {
+    let src = Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    let mut days = std::fs::read_dir(src)
+        .expect("failed to read src")
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let nr = name.strip_prefix("day")?.strip_suffix(".rs")?;
+            let is_valid = nr.len() == 2 && nr.bytes().all(|c| c.is_ascii_digit());
+            is_valid.then(|| format!("day{nr}"))
+        })
+        .collect::<Vec<_>>();
+    days.sort();
+
+    // A macro rather than the `mod` declarations themselves, because those
+    // would be looked up relative to OUT_DIR when included.
+    let mut list = String::new();
+    for day in &days {
+        write!(list, "{day}, ").unwrap();
+    }
+    let generated = format!(
+        "macro_rules! found_days {{\n    () => {{\n        framework::days!({});\n    }};\n}}\n",
+        list.trim_end()
+    );
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("days.rs");
+    std::fs::write(out, generated).expect("failed to write days.rs");
+    println!("cargo:rerun-if-changed=src");
+}
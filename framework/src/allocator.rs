@@ -0,0 +1,196 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Allocations made by a thread during some section of code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Amount of allocations, including reallocations.
+    pub count: u64,
+    /// Total amount of bytes requested by all allocations.
+    pub bytes: u64,
+    /// Highest amount of bytes allocated at once, on top of what was already
+    /// allocated before the section started.
+    pub peak: u64,
+}
+
+impl AllocStats {
+    pub fn max(self, other: AllocStats) -> AllocStats {
+        AllocStats {
+            count: self.count.max(other.count),
+            bytes: self.bytes.max(other.bytes),
+            peak: self.peak.max(other.peak),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    count: u64,
+    bytes: u64,
+    peak_allocated: usize,
+}
+
+thread_local! {
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+    static LIMIT: Cell<usize> = const { Cell::new(usize::MAX) };
+    static LIMIT_EXCEEDED: Cell<bool> = const { Cell::new(false) };
+    static COUNTING_ENABLED: Cell<bool> = const { Cell::new(false) };
+    static COUNTERS: Cell<Option<Counters>> = const { Cell::new(None) };
+}
+
+static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Wraps the system allocator to keep track of the amount of memory that is
+/// allocated by each thread, so that a memory limit can be enforced, and
+/// allocations can be counted. It is installed by the `main!` macro.
+///
+/// Memory that is freed by a different thread than the one that allocated it
+/// is attributed to the thread that frees it.
+pub struct TrackingAllocator;
+
+/// Limits the memory allocated by the current thread from here on. Once the
+/// limit is exceeded, the allocation fails, and the limit is lifted so that
+/// the failure can be handled.
+pub fn set_thread_limit(limit: Option<usize>) {
+    ALLOCATED.with(|x| x.set(0));
+    LIMIT.with(|x| x.set(limit.unwrap_or(usize::MAX)));
+    LIMIT_EXCEEDED.with(|x| x.set(false));
+}
+
+/// Whether an allocation failed because the current thread exceeded its limit.
+pub fn thread_limit_exceeded() -> bool {
+    LIMIT_EXCEEDED.with(Cell::get)
+}
+
+/// Enables counting allocations with [`count_allocations`] on the current
+/// thread. This is opt-in, because counting slightly slows down allocations.
+pub fn set_counting(enabled: bool) {
+    COUNTING_ENABLED.with(|x| x.set(enabled));
+}
+
+/// Executes `f`, and counts the allocations it made on the current thread.
+/// Returns `None` for the statistics when counting is not enabled, or when the
+/// [`TrackingAllocator`] is not the global allocator.
+pub fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !COUNTING_ENABLED.with(Cell::get) || !IS_INSTALLED.load(Ordering::Relaxed) {
+        return (f(), None);
+    }
+    let start_allocated = ALLOCATED.with(Cell::get);
+    COUNTERS.with(|x| {
+        x.set(Some(Counters {
+            count: 0,
+            bytes: 0,
+            peak_allocated: start_allocated,
+        }))
+    });
+    let result = f();
+    let counters = COUNTERS.with(|x| x.take()).unwrap();
+    let stats = AllocStats {
+        count: counters.count,
+        bytes: counters.bytes,
+        peak: counters.peak_allocated.saturating_sub(start_allocated) as u64,
+    };
+    (result, Some(stats))
+}
+
+fn count(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        if let Some(mut c) = counters.get() {
+            c.count += 1;
+            c.bytes += size as u64;
+            c.peak_allocated = c.peak_allocated.max(ALLOCATED.with(Cell::get));
+            counters.set(Some(c));
+        }
+    });
+}
+
+fn reserve(size: usize) -> bool {
+    let reserved = ALLOCATED.try_with(|allocated| {
+        let new = allocated.get().saturating_add(size);
+        if new > LIMIT.with(Cell::get) {
+            LIMIT.with(|x| x.set(usize::MAX));
+            LIMIT_EXCEEDED.with(|x| x.set(true));
+            return false;
+        }
+        allocated.set(new);
+        true
+    });
+    // During thread teardown the thread locals may no longer be accessible.
+    reserved.unwrap_or(true)
+}
+
+fn release(size: usize) {
+    let _ = ALLOCATED.try_with(|allocated| allocated.set(allocated.get().saturating_sub(size)));
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !IS_INSTALLED.load(Ordering::Relaxed) {
+            IS_INSTALLED.store(true, Ordering::Relaxed);
+        }
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = System.alloc(layout);
+        if ptr.is_null() {
+            release(layout.size());
+        } else {
+            count(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = System.alloc_zeroed(layout);
+        if ptr.is_null() {
+            release(layout.size());
+        } else {
+            count(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        release(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let old_size = layout.size();
+        if new_size > old_size && !reserve(new_size - old_size) {
+            return std::ptr::null_mut();
+        }
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if new_ptr.is_null() {
+            release(new_size.saturating_sub(old_size));
+            return new_ptr;
+        }
+        if new_size < old_size {
+            release(old_size - new_size);
+        }
+        count(new_size);
+        new_ptr
+    }
+}
+
+/// Formats an amount of bytes using binary prefixes, e.g. `1.50 GiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.2} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Verified,
+    Mismatch { expected: String },
+    Unknown,
+    Recorded,
+}
+
+impl Verification {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verification::Verified => "verified",
+            Verification::Mismatch { .. } => "mismatch",
+            Verification::Unknown => "unknown",
+            Verification::Recorded => "recorded",
+        }
+    }
+
+    pub fn expected(&self) -> Option<&str> {
+        match self {
+            Verification::Mismatch { expected } => Some(expected),
+            _ => None,
+        }
+    }
+}
+
+/// Known answers, stored as `answers/XX.txt`, with the first line holding the
+/// answer to part 1 and the second line the answer to part 2. An empty line
+/// means the answer is unknown. Answers for a named input variant are stored
+/// as `answers/XX.NAME.txt`. The `answers` directory is in the crate root.
+#[derive(Debug)]
+pub struct Answers {
+    dir: PathBuf,
+}
+
+impl Answers {
+    pub fn new(root: &Path) -> Answers {
+        Answers {
+            dir: root.join("answers"),
+        }
+    }
+
+    pub fn path(&self, day: u32, variant: Option<&str>) -> PathBuf {
+        match variant {
+            Some(name) => self.dir.join(format!("{day:0>2}.{name}.txt")),
+            None => self.dir.join(format!("{day:0>2}.txt")),
+        }
+    }
+
+    pub fn load(&self, day: u32, variant: Option<&str>) -> Result<[Option<String>; 2]> {
+        let path = self.path(day, variant);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok([None, None]),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        let mut lines = contents.lines().map(|line| line.trim_end_matches('\r'));
+        let mut next = || lines.next().filter(|line| !line.is_empty()).map(unescape);
+        Ok([next(), next()])
+    }
+
+    /// Stores the given answers, keeping any existing answer for a part that
+    /// is `None`.
+    pub fn store(&self, day: u32, variant: Option<&str>, answers: [Option<&str>; 2]) -> Result<()> {
+        let mut stored = self.load(day, variant)?;
+        for (stored, answer) in stored.iter_mut().zip(answers) {
+            if let Some(answer) = answer {
+                *stored = Some(answer.to_owned());
+            }
+        }
+        let contents = stored
+            .iter()
+            .map(|answer| answer.as_deref().map(escape).unwrap_or_default() + "\n")
+            .collect::<String>();
+
+        let path = self.path(day, variant);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Compares the answers against the known answers, and stores them as the
+    /// new known answers if `record` is set.
+    pub fn check(
+        &self,
+        day: u32,
+        variant: Option<&str>,
+        actual: [Option<&str>; 2],
+        record: bool,
+    ) -> Result<[Option<Verification>; 2]> {
+        let expected = self.load(day, variant)?;
+        let mut verifications = [None, None];
+        for ((verification, expected), actual) in
+            verifications.iter_mut().zip(&expected).zip(actual)
+        {
+            *verification = actual.map(|actual| verify(expected.as_deref(), actual));
+        }
+        if record {
+            self.store(day, variant, actual)?;
+            for verification in verifications.iter_mut().flatten() {
+                if *verification != Verification::Verified {
+                    *verification = Verification::Recorded;
+                }
+            }
+        }
+        Ok(verifications)
+    }
+}
+
+pub fn verify(expected: Option<&str>, actual: &str) -> Verification {
+    match expected {
+        None => Verification::Unknown,
+        Some(expected) if expected == actual => Verification::Verified,
+        Some(expected) => Verification::Mismatch {
+            expected: expected.to_owned(),
+        },
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut answer = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => answer.push('\n'),
+            ('\\', Some('\\')) => answer.push('\\'),
+            _ => {
+                answer.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    answer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        for answer in ["1234", "a\nb", "\\n", "x\\\ny\\"] {
+            assert_eq!(answer, unescape(&escape(answer)));
+            assert!(!escape(answer).contains('\n'));
+        }
+    }
+}
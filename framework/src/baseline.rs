@@ -0,0 +1,609 @@
+use crate::{
+    bench::{BenchSummary, Stats},
+    cli::BaselineOptions,
+};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{ErrorKind, Write as _},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const HISTORY_HEADER: &str =
+    "timestamp,commit,rustc,machine,day,variant,phase,iterations,min_ns,median_ns,mean_ns,p95_ns,stddev_ns,outliers";
+
+/// Describes where a set of benchmark results came from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Environment {
+    pub timestamp: u64,
+    pub commit: String,
+    pub rustc: String,
+    pub machine: String,
+}
+
+impl Environment {
+    pub fn detect() -> Environment {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Environment {
+            timestamp,
+            commit: detect_commit().unwrap_or_else(|| "unknown".to_owned()),
+            rustc: env!("FRAMEWORK_RUSTC_VERSION").to_owned(),
+            machine: detect_machine(),
+        }
+    }
+}
+
+fn detect_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+    let commit = git(&["rev-parse", "--short=12", "HEAD"])?;
+    let is_dirty = !git(&["status", "--porcelain", "--untracked-files=no"])?.is_empty();
+    Some(if is_dirty { commit + "-dirty" } else { commit })
+}
+
+fn detect_machine() -> String {
+    let hostname = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|x| x.trim().to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+    let cpu = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|cpuinfo| {
+            cpuinfo
+                .lines()
+                .find_map(|line| line.strip_prefix("model name"))
+                .and_then(|line| line.split_once(':'))
+                .map(|(_, model)| model.trim().to_owned())
+        });
+    let mut machine = format!(
+        "{hostname} ({}-{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS
+    );
+    if let Some(cpu) = cpu {
+        write!(machine, ", {cpu}").unwrap();
+    }
+    machine.push(')');
+    machine
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u32,
+    /// Name of the input variant, `None` for the default input.
+    pub variant: Option<String>,
+    pub phase: &'static str,
+    pub iterations: u32,
+    pub stats: Stats,
+}
+
+/// The directory of the baselines and history of a year, `baselines/YEAR` in
+/// the crate root.
+pub fn dir(root: &Path, year: u32) -> PathBuf {
+    root.join("baselines").join(year.to_string())
+}
+
+/// A named snapshot of benchmark results, stored as `NAME.tsv` in [`dir`].
+/// Saving merges new results into it, so every entry keeps the environment it
+/// was measured in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Baseline {
+    pub name: String,
+    pub entries: Vec<(Environment, Entry)>,
+}
+
+fn phases(summary: &BenchSummary) -> impl Iterator<Item = (&'static str, &Stats)> {
+    let parts = [("pt1", &summary.pt1), ("pt2", &summary.pt2)]
+        .into_iter()
+        .filter_map(|(phase, pt)| Some((phase, &pt.as_ref()?.stats)));
+    let prepare = summary.prepare.as_ref().map(|stats| ("prepare", stats));
+    std::iter::once(("parse", &summary.parse))
+        .chain(prepare)
+        .chain(parts)
+}
+
+fn parse_phase(phase: &str) -> Option<&'static str> {
+    ["parse", "prepare", "pt1", "pt2"]
+        .into_iter()
+        .find(|&p| p == phase)
+}
+
+fn parse_variant(variant: &str) -> Option<String> {
+    (!variant.is_empty()).then(|| variant.to_owned())
+}
+
+impl Baseline {
+    fn path(dir: &Path, name: &str) -> PathBuf {
+        dir.join(format!("{name}.tsv"))
+    }
+
+    pub fn new(name: &str) -> Baseline {
+        Baseline {
+            name: name.to_owned(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn load(dir: &Path, name: &str) -> Result<Baseline> {
+        let path = Self::path(dir, name);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read baseline {}", path.display()))?;
+        Self::parse(name, &contents).with_context(|| format!("invalid baseline {}", path.display()))
+    }
+
+    /// Loads the baseline, or creates an empty one when it does not exist.
+    pub fn load_or_new(dir: &Path, name: &str) -> Result<Baseline> {
+        match std::fs::metadata(Self::path(dir, name)) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::new(name)),
+            _ => Self::load(dir, name),
+        }
+    }
+
+    /// Parses a baseline. Older baselines have a single environment in `# `
+    /// comments at the top, rather than one on every line.
+    fn parse(name: &str, contents: &str) -> Result<Baseline> {
+        let mut baseline = Baseline::new(name);
+        let mut shared = Environment::default();
+        for line in contents.lines() {
+            if let Some(meta) = line.strip_prefix("# ") {
+                let (key, value) = meta
+                    .split_once(": ")
+                    .ok_or_else(|| anyhow!("bad line: {line}"))?;
+                let env = &mut shared;
+                match key {
+                    "timestamp" => env.timestamp = value.parse()?,
+                    "commit" => env.commit = value.to_owned(),
+                    "rustc" => env.rustc = value.to_owned(),
+                    "machine" => env.machine = value.to_owned(),
+                    _ => {}
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with("day\t") {
+                continue;
+            }
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let bad_line = || anyhow!("bad line: {line}");
+            let environment = match fields[..] {
+                [_, _, _, _, _, _, _, _, _, _] => shared.clone(),
+                [_, _, _, _, _, _, _, _, _, _, timestamp, commit, rustc, machine] => Environment {
+                    timestamp: timestamp.parse()?,
+                    commit: commit.to_owned(),
+                    rustc: rustc.to_owned(),
+                    machine: machine.to_owned(),
+                },
+                _ => return Err(bad_line()),
+            };
+            let nanos =
+                |i: usize| -> Result<Duration> { Ok(Duration::from_nanos(fields[i].parse()?)) };
+            let entry = Entry {
+                day: fields[0].parse()?,
+                variant: parse_variant(fields[1]),
+                phase: parse_phase(fields[2]).ok_or_else(bad_line)?,
+                iterations: fields[3].parse()?,
+                stats: Stats {
+                    min: nanos(4)?,
+                    median: nanos(5)?,
+                    mean: nanos(6)?,
+                    p95: nanos(7)?,
+                    stddev: nanos(8)?,
+                    outliers: fields[9].parse()?,
+                },
+            };
+            baseline.entries.push((environment, entry));
+        }
+        Ok(baseline)
+    }
+
+    fn serialize(&self) -> String {
+        let mut contents = String::from(
+            "day\tvariant\tphase\titerations\tmin_ns\tmedian_ns\tmean_ns\tp95_ns\tstddev_ns\toutliers\t\
+             timestamp\tcommit\trustc\tmachine\n",
+        );
+        for (env, entry) in &self.entries {
+            let s = &entry.stats;
+            writeln!(
+                contents,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                entry.day,
+                entry.variant.as_deref().unwrap_or_default(),
+                entry.phase,
+                entry.iterations,
+                s.min.as_nanos(),
+                s.median.as_nanos(),
+                s.mean.as_nanos(),
+                s.p95.as_nanos(),
+                s.stddev.as_nanos(),
+                s.outliers,
+                env.timestamp,
+                env.commit,
+                env.rustc,
+                env.machine
+            )
+            .unwrap();
+        }
+        contents
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = Self::path(dir, &self.name);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, self.serialize())
+            .with_context(|| format!("failed to write baseline {}", path.display()))
+    }
+
+    pub fn get(&self, day: u32, variant: Option<&str>, phase: &str) -> Option<&Entry> {
+        self.entries.iter().map(|(_, entry)| entry).find(|entry| {
+            entry.day == day && entry.variant.as_deref() == variant && entry.phase == phase
+        })
+    }
+
+    /// The environments the entries were measured in, without duplicates.
+    pub fn environments(&self) -> Vec<&Environment> {
+        let mut environments = Vec::new();
+        for (env, _) in &self.entries {
+            if !environments.contains(&env) {
+                environments.push(env);
+            }
+        }
+        environments
+    }
+
+    /// Replaces all entries for the day and variant with the results in the
+    /// summary, measured in the given environment.
+    pub fn insert(
+        &mut self,
+        environment: &Environment,
+        day: u32,
+        variant: Option<&str>,
+        summary: &BenchSummary,
+    ) {
+        self.entries
+            .retain(|(_, entry)| entry.day != day || entry.variant.as_deref() != variant);
+        self.entries.extend(phases(summary).map(|(phase, stats)| {
+            let entry = Entry {
+                day,
+                variant: variant.map(str::to_owned),
+                phase,
+                iterations: summary.iterations,
+                stats: *stats,
+            };
+            (environment.clone(), entry)
+        }));
+        self.entries.sort_by(|(_, a), (_, b)| {
+            (a.day, &a.variant, a.phase).cmp(&(b.day, &b.variant, b.phase))
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Delta {
+    pub phase: &'static str,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Relative change of the median, in percent. `None` if the baseline was
+    /// too fast to measure a meaningful difference.
+    pub change: Option<f64>,
+}
+
+impl Delta {
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        matches!(self.change, Some(change) if change > threshold)
+    }
+}
+
+/// Compares the medians of each phase against the baseline.
+pub fn compare(
+    baseline: &Baseline,
+    day: u32,
+    variant: Option<&str>,
+    summary: &BenchSummary,
+) -> Vec<Delta> {
+    phases(summary)
+        .filter_map(|(phase, stats)| {
+            let entry = baseline.get(day, variant, phase)?;
+            let (baseline, current) = (entry.stats.median, stats.median);
+            let change = (!baseline.is_zero())
+                .then(|| (current.as_nanos() as f64 / baseline.as_nanos() as f64 - 1.0) * 100.0);
+            Some(Delta {
+                phase,
+                baseline,
+                current,
+                change,
+            })
+        })
+        .collect()
+}
+
+/// Keeps track of the baselines and history during a single bench run.
+pub struct Tracker {
+    dir: PathBuf,
+    environment: Environment,
+    compare: Option<Baseline>,
+    save: Option<Baseline>,
+    history: bool,
+}
+
+impl Tracker {
+    pub fn new(root: &Path, year: u32, options: &BaselineOptions) -> Result<Tracker> {
+        let dir = dir(root, year);
+        let environment = Environment::detect();
+        let compare = options
+            .compare
+            .as_deref()
+            .map(|name| Baseline::load(&dir, name))
+            .transpose()?;
+        let save = options
+            .save
+            .as_deref()
+            .map(|name| Baseline::load_or_new(&dir, name))
+            .transpose()?;
+        Ok(Tracker {
+            dir,
+            environment,
+            compare,
+            save,
+            history: options.history,
+        })
+    }
+
+    pub fn baseline(&self) -> Option<&Baseline> {
+        self.compare.as_ref()
+    }
+
+    /// Records the results of a day, and returns how they compare against
+    /// the baseline, if any.
+    pub fn record(
+        &mut self,
+        day: u32,
+        variant: Option<&str>,
+        summary: &BenchSummary,
+    ) -> Result<Vec<Delta>> {
+        if let Some(save) = &mut self.save {
+            save.insert(&self.environment, day, variant, summary);
+        }
+        if self.history {
+            History::append(&self.dir, &self.environment, day, variant, summary)?;
+        }
+        Ok(self
+            .compare
+            .as_ref()
+            .map(|baseline| compare(baseline, day, variant, summary))
+            .unwrap_or_default())
+    }
+
+    /// Saves the baseline.
+    pub fn finish(self) -> Result<()> {
+        match self.save {
+            Some(save) => save.save(&self.dir),
+            None => Ok(()),
+        }
+    }
+}
+
+/// History of all recorded benchmark runs, as an append-only CSV file, stored
+/// as `history.csv` in [`dir`].
+pub struct History;
+
+impl History {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join("history.csv")
+    }
+
+    pub fn append(
+        dir: &Path,
+        environment: &Environment,
+        day: u32,
+        variant: Option<&str>,
+        summary: &BenchSummary,
+    ) -> Result<()> {
+        let path = Self::path(dir);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+
+        let mut contents = String::new();
+        if file.metadata()?.len() == 0 {
+            contents.push_str(HISTORY_HEADER);
+            contents.push('\n');
+        }
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+        for (phase, s) in phases(summary) {
+            writeln!(
+                contents,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                environment.timestamp,
+                quote(&environment.commit),
+                quote(&environment.rustc),
+                quote(&environment.machine),
+                day,
+                quote(variant.unwrap_or_default()),
+                phase,
+                summary.iterations,
+                s.min.as_nanos(),
+                s.median.as_nanos(),
+                s.mean.as_nanos(),
+                s.p95.as_nanos(),
+                s.stddev.as_nanos(),
+                s.outliers
+            )
+            .unwrap();
+        }
+        file.write_all(contents.as_bytes())
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Loads all history entries, oldest first.
+    pub fn load(dir: &Path) -> Result<Vec<(Environment, Entry)>> {
+        let path = Self::path(dir);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.is_empty() && *line != HISTORY_HEADER)
+            .map(|line| {
+                Self::parse_line(line).with_context(|| format!("invalid history line: {line}"))
+            })
+            .collect()
+    }
+
+    fn parse_line(line: &str) -> Result<(Environment, Entry)> {
+        let fields = split_csv(line);
+        if fields.len() != 14 {
+            return Err(anyhow!("expected 14 fields"));
+        }
+        let nanos = |i: usize| -> Result<Duration> { Ok(Duration::from_nanos(fields[i].parse()?)) };
+        let environment = Environment {
+            timestamp: fields[0].parse()?,
+            commit: fields[1].clone(),
+            rustc: fields[2].clone(),
+            machine: fields[3].clone(),
+        };
+        let entry = Entry {
+            day: fields[4].parse()?,
+            variant: parse_variant(&fields[5]),
+            phase: parse_phase(&fields[6]).ok_or_else(|| anyhow!("unknown phase"))?,
+            iterations: fields[7].parse()?,
+            stats: Stats {
+                min: nanos(8)?,
+                median: nanos(9)?,
+                mean: nanos(10)?,
+                p95: nanos(11)?,
+                stddev: nanos(12)?,
+                outliers: fields[13].parse()?,
+            },
+        };
+        Ok((environment, entry))
+    }
+}
+
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => fields.push(String::new()),
+            (c, _) => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Formats a unix timestamp as an ISO 8601 date and time in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    // Converts days since the epoch to a civil date, see:
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let (days, secs) = ((timestamp / 86400) as i64, timestamp % 86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baseline_round_trip() {
+        let stats = |median| Stats {
+            min: Duration::from_nanos(median - 10),
+            median: Duration::from_nanos(median),
+            mean: Duration::from_nanos(median + 2),
+            p95: Duration::from_nanos(median + 30),
+            stddev: Duration::from_nanos(5),
+            outliers: 3,
+        };
+        let old = Environment {
+            timestamp: 1640390400,
+            commit: "0123456789ab-dirty".to_owned(),
+            rustc: "rustc 1.59.0-nightly".to_owned(),
+            machine: "host (x86_64-linux)".to_owned(),
+        };
+        let new = Environment {
+            timestamp: 1640413815,
+            machine: "other (aarch64-macos)".to_owned(),
+            ..old.clone()
+        };
+        let mut baseline = Baseline::new("test");
+        let entry = Entry {
+            day: 19,
+            variant: Some("stress".to_owned()),
+            phase: "pt2",
+            iterations: 100,
+            stats: stats(2000),
+        };
+        baseline.entries.push((old.clone(), entry));
+        baseline.insert(
+            &new,
+            19,
+            None,
+            &BenchSummary {
+                iterations: 100,
+                warmup_iterations: 4,
+                parse: stats(100),
+                parse_allocs: None,
+                prepare: None,
+                prepare_allocs: None,
+                pt1: None,
+                pt2: None,
+            },
+        );
+        assert_eq!(vec![&new, &old], baseline.environments());
+
+        let contents = baseline.serialize();
+        assert_eq!(baseline, Baseline::parse("test", &contents).unwrap());
+
+        let legacy = "# timestamp: 1640390400\n# commit: 0123456789ab-dirty\n\
+                      # rustc: rustc 1.59.0-nightly\n# machine: host (x86_64-linux)\n\
+                      day\tvariant\tphase\titerations\tmin_ns\tmedian_ns\tmean_ns\tp95_ns\tstddev_ns\toutliers\n\
+                      19\tstress\tpt2\t100\t1990\t2000\t2002\t2030\t5\t3\n";
+        let legacy = Baseline::parse("test", legacy).unwrap();
+        assert_eq!(baseline.entries[1..], legacy.entries);
+    }
+
+    #[test]
+    fn csv_and_timestamps() {
+        assert_eq!(
+            vec!["1", "a \"b\", c", "", "d"],
+            split_csv(r#"1,"a ""b"", c",,d"#)
+        );
+        assert_eq!("1970-01-01 00:00:00Z", format_timestamp(0));
+        assert_eq!("2021-12-25 06:30:15Z", format_timestamp(1640413815));
+    }
+}
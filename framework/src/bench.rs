@@ -0,0 +1,422 @@
+use crate::{
+    allocator::{self, format_bytes, AllocStats},
+    bench_day,
+    cli::Options,
+    day::{Answer, BenchPart, Day, Part},
+    output::{self, RecordWriter},
+    print_day_header, render,
+    sandbox::Failure,
+};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Time spent running the day before measuring. At least one iteration
+    /// is executed, unless this is zero.
+    pub warmup: Duration,
+    pub max_iterations: u32,
+    pub max_time: Duration,
+    /// Whether to count the allocations of each phase.
+    pub allocations: bool,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: Duration::from_millis(500),
+            max_iterations: 10_000,
+            max_time: Duration::from_secs(5),
+            allocations: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+    /// Amount of samples outside of the Tukey fences, 1.5 times the
+    /// interquartile range below the first, or above the third quartile.
+    pub outliers: u32,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty());
+        samples.sort_unstable();
+        let n = samples.len();
+
+        let percentile = |p: f64| samples[((p * n as f64).ceil() as usize).clamp(1, n) - 1];
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+
+        let nanos = |d: &Duration| d.as_nanos() as f64;
+        let mean = samples.iter().map(nanos).sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            samples
+                .iter()
+                .map(|d| (nanos(d) - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        let (q1, q3) = (nanos(&percentile(0.25)), nanos(&percentile(0.75)));
+        let (low, high) = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
+        let outliers = samples
+            .iter()
+            .filter(|d| !(low..=high).contains(&nanos(d)))
+            .count() as u32;
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            p95: percentile(0.95),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+            outliers,
+        }
+    }
+}
+
+pub struct PartSummary {
+    pub stats: Stats,
+    /// Allocations of a single iteration, the maximum of all iterations.
+    pub allocs: Option<AllocStats>,
+    pub output: Result<Answer>,
+}
+
+pub struct BenchSummary {
+    pub iterations: u32,
+    pub warmup_iterations: u32,
+    pub parse: Stats,
+    pub parse_allocs: Option<AllocStats>,
+    /// Only for days with a shared stage between parsing and the parts.
+    pub prepare: Option<Stats>,
+    pub prepare_allocs: Option<AllocStats>,
+    pub pt1: Option<PartSummary>,
+    pub pt2: Option<PartSummary>,
+}
+
+pub enum BenchResult {
+    NoInput(anyhow::Error),
+    Rejected(anyhow::Error),
+    ParseFailed(anyhow::Error),
+    Aborted(Failure),
+    Ran(Box<BenchSummary>),
+}
+
+impl BenchResult {
+    pub fn is_success(&self) -> bool {
+        match self {
+            BenchResult::NoInput(_)
+            | BenchResult::Rejected(_)
+            | BenchResult::ParseFailed(_)
+            | BenchResult::Aborted(_) => false,
+            BenchResult::Ran(summary) => [&summary.pt1, &summary.pt2]
+                .into_iter()
+                .all(|pt| !matches!(pt, Some(PartSummary { output: Err(_), .. }))),
+        }
+    }
+}
+
+fn max_allocs(a: Option<AllocStats>, b: Option<AllocStats>) -> Option<AllocStats> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+struct PartSamples {
+    samples: Vec<Duration>,
+    allocs: Option<AllocStats>,
+    output: Result<Answer>,
+}
+
+impl PartSamples {
+    /// Records an iteration of a part, if it ran. The answer of the first
+    /// iteration is kept, and every later one must be identical to it.
+    fn record(samples: &mut Option<PartSamples>, part: Option<BenchPart>, iteration: u32) {
+        match samples {
+            Some(samples) => samples.push(part, iteration),
+            None => {
+                *samples = part.map(|first| PartSamples {
+                    samples: vec![first.duration],
+                    allocs: first.allocs,
+                    output: first.output,
+                })
+            }
+        }
+    }
+
+    fn push(&mut self, part: Option<BenchPart>, iteration: u32) {
+        let part = match part {
+            Some(part) => part,
+            None => return,
+        };
+        self.samples.push(part.duration);
+        self.allocs = max_allocs(self.allocs, part.allocs);
+        let expected = match &self.output {
+            Ok(expected) => expected,
+            Err(_) => return,
+        };
+        let actual = match &part.output {
+            Ok(actual) => render::plain(actual),
+            Err(e) => {
+                self.output = Err(anyhow!("iteration {iteration} failed: {e}"));
+                return;
+            }
+        };
+        let expected = render::plain(expected);
+        if actual != expected {
+            self.output = Err(anyhow!(
+                "answer changed on iteration {iteration}, from {expected} to {actual}"
+            ));
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        self.output.is_ok()
+    }
+
+    /// Drops the measurements of the warmup, keeping its answer.
+    fn clear_samples(&mut self) {
+        self.samples.clear();
+        self.allocs = None;
+    }
+
+    fn summarize(mut self) -> PartSummary {
+        PartSummary {
+            stats: Stats::from_samples(&mut self.samples),
+            allocs: self.allocs,
+            output: self.output,
+        }
+    }
+}
+
+pub fn run(day: &dyn Day, input: &[u8], part: Option<Part>, config: &BenchConfig) -> BenchResult {
+    // Validated once up front, it's not part of the measured phases.
+    if let Err(e) = day.validate(input) {
+        return BenchResult::Rejected(e);
+    }
+    allocator::set_counting(config.allocations);
+    let result = run_iterations(day, input, part, config);
+    allocator::set_counting(false);
+    result
+}
+
+fn run_iterations(
+    day: &dyn Day,
+    input: &[u8],
+    part: Option<Part>,
+    config: &BenchConfig,
+) -> BenchResult {
+    // The answers of the warmup are checked like those of the measured
+    // iterations, which are numbered after it.
+    let mut pt1 = None;
+    let mut pt2 = None;
+    let is_ok = |pt: &Option<PartSamples>| pt.iter().all(PartSamples::is_ok);
+
+    let warmup_start = Instant::now();
+    let mut warmup_iterations = 0;
+    while warmup_start.elapsed() < config.warmup && is_ok(&pt1) && is_ok(&pt2) {
+        let outputs = match day.exec_bench(input, part) {
+            Ok(x) => x,
+            Err(e) => return BenchResult::ParseFailed(e),
+        };
+        warmup_iterations += 1;
+        PartSamples::record(&mut pt1, outputs.pt1, warmup_iterations);
+        PartSamples::record(&mut pt2, outputs.pt2, warmup_iterations);
+    }
+    for pt in [&mut pt1, &mut pt2].into_iter().flatten() {
+        pt.clear_samples();
+    }
+
+    let start_time = Instant::now();
+    let mut parse_samples = Vec::new();
+    let mut parse_allocs = None;
+    let mut prepare_samples = Vec::new();
+    let mut prepare_allocs = None;
+    let mut iterations = 0u32;
+    while iterations == 0
+        || iterations < config.max_iterations
+            && start_time.elapsed() < config.max_time
+            && is_ok(&pt1)
+            && is_ok(&pt2)
+    {
+        let outputs = match day.exec_bench(input, part) {
+            Ok(x) => x,
+            Err(e) => return BenchResult::ParseFailed(e),
+        };
+        iterations += 1;
+        parse_samples.push(outputs.parse);
+        parse_allocs = max_allocs(parse_allocs, outputs.parse_allocs);
+        if let Some((duration, allocs)) = outputs.prepare {
+            prepare_samples.push(duration);
+            prepare_allocs = max_allocs(prepare_allocs, allocs);
+        }
+        let iteration = warmup_iterations + iterations;
+        PartSamples::record(&mut pt1, outputs.pt1, iteration);
+        PartSamples::record(&mut pt2, outputs.pt2, iteration);
+    }
+
+    BenchResult::Ran(Box::new(BenchSummary {
+        iterations,
+        warmup_iterations,
+        parse: Stats::from_samples(&mut parse_samples),
+        parse_allocs,
+        prepare: (!prepare_samples.is_empty()).then(|| Stats::from_samples(&mut prepare_samples)),
+        prepare_allocs,
+        pt1: pt1.map(PartSamples::summarize),
+        pt2: pt2.map(PartSamples::summarize),
+    }))
+}
+
+/// Benchmarks a day against generated inputs of every selected scale, to see
+/// how it scales with the size of its input.
+pub(crate) fn sweep_day(
+    options: &Options,
+    writer: &mut RecordWriter,
+    day: &'static dyn Day,
+) -> bool {
+    let day_nr = day.nr();
+    let is_text = writer.is_text();
+    if is_text {
+        print_day_header(day_nr, None);
+    }
+    let mut success = true;
+    for (i, &scale) in options.scales.iter().enumerate() {
+        let input = match day.generate_input(options.seed, scale) {
+            Some(input) => input,
+            None => {
+                let result = BenchResult::NoInput(anyhow!("the day has no input generator"));
+                writer.write(&output::scale_record(day_nr, scale, None, &result, options));
+                if is_text {
+                    println!(" :: {}", "no input generator".bright_black());
+                }
+                return true;
+            }
+        };
+        let input_bytes = input.len();
+        let result = bench_day(day, Ok(input), options);
+        success &= result.is_success();
+        writer.write(&output::scale_record(
+            day_nr,
+            scale,
+            Some(input_bytes),
+            &result,
+            options,
+        ));
+        if !is_text {
+            continue;
+        }
+        if i == 0 {
+            println!(" :: generated inputs, seed {}", options.seed);
+            print!("{:>9} {:>12} {:>12}", "scale", "input", "parse");
+            if day.has_prepare() {
+                print!(" {:>12}", "prepare");
+            }
+            println!(" {:>12} {:>12}", "pt1", "pt2");
+        }
+        let input_bytes = format_bytes(input_bytes);
+        print!("{:>9} {input_bytes:>12}", scale.to_string().bright_green());
+        let summary = match result {
+            BenchResult::Ran(summary) => summary,
+            BenchResult::NoInput(e) | BenchResult::Rejected(e) | BenchResult::ParseFailed(e) => {
+                println!(" :: {}", format!("error: {e:#}").bright_red());
+                continue;
+            }
+            BenchResult::Aborted(failure) => {
+                println!(" :: {}", failure.to_string().bright_red());
+                continue;
+            }
+        };
+        let fmt = |stats: Option<&Stats>| match stats {
+            Some(stats) => format!("{:.2?}", stats.median).white().bold(),
+            None => "-".bright_black(),
+        };
+        print!(" {:>12}", fmt(Some(&summary.parse)));
+        if day.has_prepare() {
+            print!(" {:>12}", fmt(summary.prepare.as_ref()));
+        }
+        for pt in [&summary.pt1, &summary.pt2] {
+            print!(" {:>12}", fmt(pt.as_ref().map(|pt| &pt.stats)));
+        }
+        for pt in [summary.pt1, summary.pt2].into_iter().flatten() {
+            if let Err(e) = pt.output {
+                print!(" {}", format!("error: {e}").bright_red());
+            }
+        }
+        println!();
+    }
+    success
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::ParseResult;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn parse(input: &[u8]) -> ParseResult<'_, usize> {
+        Ok((input.len(), &[]))
+    }
+
+    fn stable(input: &usize) -> usize {
+        *input
+    }
+
+    fn unstable(_: &usize) -> usize {
+        CALLS.fetch_add(1, Ordering::Relaxed)
+    }
+
+    crate::day!(1, parse => stable, unstable);
+
+    #[test]
+    fn checks_warmup_answers() {
+        let config = BenchConfig {
+            warmup: Duration::from_secs(60),
+            max_iterations: 1,
+            ..BenchConfig::default()
+        };
+        let summary = match run(&day(), b"abc", None, &config) {
+            BenchResult::Ran(summary) => summary,
+            _ => panic!("the day didn't run"),
+        };
+        assert_eq!(2, summary.warmup_iterations);
+        assert_eq!(1, summary.iterations);
+        assert!(summary.pt1.unwrap().output.is_ok());
+        let error = summary.pt2.unwrap().output.unwrap_err().to_string();
+        assert_eq!("answer changed on iteration 2, from 0 to 1", error);
+    }
+
+    #[test]
+    fn stats() {
+        let mut samples = [5, 1, 4, 2, 3, 3, 100, 3]
+            .map(Duration::from_nanos)
+            .to_vec();
+        let stats = Stats::from_samples(&mut samples);
+        assert_eq!(Duration::from_nanos(1), stats.min);
+        assert_eq!(Duration::from_nanos(3), stats.median);
+        assert_eq!(Duration::from_nanos(15), stats.mean);
+        assert_eq!(Duration::from_nanos(100), stats.p95);
+        assert_eq!(Duration::from_nanos(34), stats.stddev);
+        assert_eq!(1, stats.outliers);
+
+        let stats = Stats::from_samples(&mut [Duration::from_micros(7)]);
+        assert_eq!(Duration::from_micros(7), stats.median);
+        assert_eq!(Duration::ZERO, stats.stddev);
+        assert_eq!(0, stats.outliers);
+    }
+}
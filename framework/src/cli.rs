@@ -0,0 +1,798 @@
+use crate::{bench::BenchConfig, day::Part, output::OutputFormat, sandbox::Limits};
+use std::{path::PathBuf, time::Duration};
+use thiserror::Error;
+
+const USAGE: &str = "\
+Usage: {program} [COMMAND] [DAYS...] [OPTIONS]
+
+Commands:
+    run      Run the selected days and print their answers (default)
+    bench    Benchmark the selected days
+    list     List all available days
+    history  Show the benchmark history of the selected days
+    inputs verify
+             Check the cached inputs of the selected days for corruption
+    inputs encrypt
+             Encrypt the plaintext inputs of the selected days with the
+             input key from $AOC_INPUT_KEY, $AOC_INPUT_KEY_FILE, key_file in
+             aoc.conf or input_key.txt in the crate root
+    examples Extract the examples from the puzzle descriptions of the
+             selected days, which are downloaded once and then read from
+             puzzles/XX.html in the input directory
+    submit <DAY> <PART>
+             Run a part of a day, and submit its answer to the website,
+             unless it is known to be wrong or the cooldown of the previous
+             answer has not ended yet, see submissions.tsv in the input
+             directory
+
+Days:
+    Any combination of single days, ranges and comma separated lists,
+    e.g. `5`, `3-7`, `1,4,9` or `1-3,10 20`. Defaults to all days.
+
+Options:
+    -p, --part <1|2>          Only execute the given part
+    -i, --input <PATH|->      Read the input from PATH, or from stdin when `-`
+                              is given (requires exactly one day)
+    -v, --variant <NAME>      Only use the input variant NAME, from
+                              inputs/XX/NAME.txt or inputs/XX.NAME.txt, or
+                              `default` for inputs/XX.txt (defaults to all)
+    -f, --format <FORMAT>     Output format: text (default), json (one object
+                              per line) or csv
+    -r, --record              Store the answers as the known answers for the
+                              selected days (run only)
+    -w, --watch               Keep running, and execute the selected days again
+                              when their input or known answers change (run only)
+    -j, --jobs <N>            Execute up to N days in parallel (defaults to the
+                              amount of CPUs for run, and 1 for bench)
+    --timeout <DURATION>      Abort a day when it takes longer than this, on
+                              top of the warmup and measuring time for bench
+    --memory-limit <SIZE>     Abort a day when it allocates more than SIZE
+                              bytes at once, e.g. `512M` or `2G`
+    --input-dir <DIR>         Directory the inputs are stored in (defaults to
+                              $AOC_INPUT_DIR, then input_dir in aoc.conf, then
+                              inputs/ in the crate root)
+    --session-file <PATH>     File with the session cookie used to download
+                              inputs and submit answers (defaults to
+                              $AOC_SESSION, then $AOC_SESSION_FILE, then
+                              session_file in aoc.conf, then session_key.txt
+                              in the crate root)
+    --refetch                 Download broken inputs again, moving the broken
+                              files to the quarantine directory (inputs
+                              verify only)
+    --write                   Add the examples, and commented out tests using
+                              them, to the tests of the day in src/dayXX.rs
+                              (examples only)
+    --pick <N,...>            Only use the examples with these numbers, instead
+                              of the first one of each part (examples only)
+    --verbose                 Report what normalizing the inputs changed, e.g.
+                              converted line endings (run and bench only)
+    --param <NAME=VALUE>      Override a parameter of the selected days, e.g.
+                              `steps=1000`, which can be repeated; `list`
+                              shows the parameters (run and bench only)
+    -h, --help                Print this help message
+
+Bench options:
+    --warmup <DURATION>       Time to run each day before measuring
+                              (default 500ms)
+    --iterations <N>          Maximum amount of measured iterations
+                              (default 10000)
+    --time <DURATION>         Maximum time spent measuring each day
+                              (default 5s)
+    --allocations             Count the allocations of each phase
+    --save-baseline <NAME>    Store the results in baselines/YEAR/NAME.tsv
+    --baseline <NAME>         Compare the results against
+                              baselines/YEAR/NAME.tsv
+    --threshold <PERCENT>     Fail when the median of a phase is more than
+                              PERCENT slower than the baseline (default 5)
+    --history                 Append the results to
+                              baselines/YEAR/history.csv
+    --scales <N,...>          Benchmark generated inputs of these scales
+                              instead of the real input, where 1 is about the
+                              size of a real input, e.g. `1,2,4,8`
+    --seed <N>                Seed for the generated inputs (default 0)
+
+    Durations are a number followed by ns, us, ms, s or m, e.g. `1.5s`, and
+    are in seconds when no unit is given.";
+
+pub fn usage(program: &str) -> String {
+    USAGE.replace("{program}", program)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    Bench,
+    List,
+    History,
+    VerifyInputs,
+    EncryptInputs,
+    Examples,
+    Submit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineOptions {
+    pub save: Option<String>,
+    pub compare: Option<String>,
+    /// Maximum allowed slowdown of the median, in percent.
+    pub threshold: f64,
+    pub history: bool,
+}
+
+impl Default for BaselineOptions {
+    fn default() -> Self {
+        BaselineOptions {
+            save: None,
+            compare: None,
+            threshold: 5.0,
+            history: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    /// Selected days, in ascending order. Empty when all days are selected.
+    pub days: Vec<u32>,
+    pub part: Option<Part>,
+    pub input: Option<InputSource>,
+    pub input_dir: Option<PathBuf>,
+    pub session_file: Option<PathBuf>,
+    /// Selected input variant, where `Some(None)` is the default input.
+    pub variant: Option<Option<String>>,
+    pub format: OutputFormat,
+    pub record: bool,
+    pub watch: bool,
+    /// Amount of days executed in parallel, `None` for the default.
+    pub jobs: Option<usize>,
+    pub limits: Limits,
+    pub bench: BenchConfig,
+    pub baseline: BaselineOptions,
+    pub refetch: bool,
+    pub write: bool,
+    /// Numbers of the selected examples, starting at 1.
+    pub pick: Vec<usize>,
+    pub verbose: bool,
+    /// Scales of the generated inputs to benchmark, empty for the real inputs.
+    pub scales: Vec<u32>,
+    pub seed: u64,
+    /// Overridden parameters of the selected days.
+    pub params: Vec<(String, i64)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cli {
+    Help,
+    Options(Box<Options>),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CliError {
+    #[error("unknown argument: {0}")]
+    UnknownArgument(String),
+    #[error("unknown subcommand: inputs {0}, expected verify or encrypt")]
+    UnknownSubcommand(String),
+    #[error("missing value for {0}")]
+    MissingValue(&'static str),
+    #[error("invalid day selection: {0}")]
+    InvalidDays(String),
+    #[error("day {0} is out of range, expected 1-25")]
+    DayOutOfRange(u32),
+    #[error("invalid part: {0}, expected 1 or 2")]
+    InvalidPart(String),
+    #[error("invalid format: {0}, expected text, json or csv")]
+    InvalidFormat(String),
+    #[error("invalid size: {0}")]
+    InvalidSize(String),
+    #[error("invalid duration: {0}")]
+    InvalidDuration(String),
+    #[error("invalid number: {0}")]
+    InvalidNumber(String),
+    #[error("invalid name: {0}, only letters, digits, `-`, `_` and `.` are allowed")]
+    InvalidName(String),
+    #[error("{0} was specified more than once")]
+    Duplicate(&'static str),
+    #[error("invalid parameter: {0}, expected NAME=VALUE with an integer value")]
+    InvalidParam(String),
+    #[error("parameter {0} was specified more than once")]
+    DuplicateParam(String),
+    #[error("submit requires a single day and a part, e.g. `submit 5 2`")]
+    SubmitUsage,
+    #[error("--input requires exactly one day to be selected")]
+    InputRequiresSingleDay,
+    #[error("--record cannot be combined with --input")]
+    RecordWithInput,
+    #[error("{0} cannot be combined with {1}")]
+    Conflict(&'static str, &'static str),
+    #[error("{0} is not supported by the {1} command")]
+    Unsupported(&'static str, &'static str),
+}
+
+impl Command {
+    fn name(self) -> &'static str {
+        match self {
+            Command::Run => "run",
+            Command::Bench => "bench",
+            Command::List => "list",
+            Command::History => "history",
+            Command::VerifyInputs => "inputs verify",
+            Command::EncryptInputs => "inputs encrypt",
+            Command::Examples => "examples",
+            Command::Submit => "submit",
+        }
+    }
+}
+
+struct Flag {
+    short: Option<&'static str>,
+    long: &'static str,
+    has_value: bool,
+    commands: &'static [Command],
+}
+
+#[rustfmt::skip]
+const FLAGS: &[Flag] = {
+    use Command::*;
+    const fn flag(short: Option<&'static str>, long: &'static str, has_value: bool, commands: &'static [Command]) -> Flag {
+        Flag { short, long, has_value, commands }
+    }
+    &[
+        flag(Some("-p"), "--part",          true,  &[Run, Bench]),
+        flag(Some("-i"), "--input",         true,  &[Run, Bench]),
+        flag(Some("-v"), "--variant",       true,  &[Run, Bench]),
+        flag(Some("-f"), "--format",        true,  &[Run, Bench, List, History, VerifyInputs, EncryptInputs, Examples, Submit]),
+        flag(Some("-r"), "--record",        false, &[Run]),
+        flag(Some("-w"), "--watch",         false, &[Run]),
+        flag(Some("-j"), "--jobs",          true,  &[Run, Bench]),
+        flag(None,       "--timeout",       true,  &[Run, Bench, Submit]),
+        flag(None,       "--memory-limit",  true,  &[Run, Bench, Submit]),
+        flag(None,       "--input-dir",     true,  &[Run, Bench, List, VerifyInputs, EncryptInputs, Examples, Submit]),
+        flag(None,       "--session-file",  true,  &[Run, Bench, VerifyInputs, Examples, Submit]),
+        flag(None,       "--warmup",        true,  &[Bench]),
+        flag(None,       "--iterations",    true,  &[Bench]),
+        flag(None,       "--time",          true,  &[Bench]),
+        flag(None,       "--allocations",   false, &[Bench]),
+        flag(None,       "--save-baseline", true,  &[Bench]),
+        flag(None,       "--baseline",      true,  &[Bench]),
+        flag(None,       "--threshold",     true,  &[Bench]),
+        flag(None,       "--history",       false, &[Bench]),
+        flag(None,       "--scales",        true,  &[Bench]),
+        flag(None,       "--seed",          true,  &[Bench]),
+        flag(None,       "--refetch",       false, &[VerifyInputs]),
+        flag(None,       "--write",         false, &[Examples]),
+        flag(None,       "--pick",          true,  &[Examples]),
+        flag(None,       "--verbose",       false, &[Run, Bench]),
+        flag(None,       "--param",         true,  &[Run, Bench]),
+    ]
+};
+
+pub fn parse_args<I>(args: I) -> Result<Cli, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some("run") => Some(Command::Run),
+        Some("bench") => Some(Command::Bench),
+        Some("list") => Some(Command::List),
+        Some("history") => Some(Command::History),
+        Some("examples") => Some(Command::Examples),
+        Some("submit") => Some(Command::Submit),
+        Some("inputs") => {
+            args.next();
+            Some(match args.peek().map(String::as_str) {
+                Some("verify") => Command::VerifyInputs,
+                Some("encrypt") => Command::EncryptInputs,
+                subcommand => {
+                    let subcommand = subcommand.unwrap_or_default().to_owned();
+                    return Err(CliError::UnknownSubcommand(subcommand));
+                }
+            })
+        }
+        _ => None,
+    };
+    if command.is_some() {
+        args.next();
+    }
+    let command = command.unwrap_or(Command::Run);
+
+    let mut options = Options {
+        command,
+        days: Vec::new(),
+        part: None,
+        input: None,
+        input_dir: None,
+        session_file: None,
+        variant: None,
+        format: OutputFormat::Text,
+        record: false,
+        watch: false,
+        jobs: None,
+        limits: Limits::default(),
+        bench: BenchConfig::default(),
+        baseline: BaselineOptions::default(),
+        refetch: false,
+        write: false,
+        pick: Vec::new(),
+        verbose: false,
+        scales: Vec::new(),
+        seed: 0,
+        params: Vec::new(),
+    };
+    let mut seen = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(Cli::Help);
+        }
+        if arg.starts_with(|c: char| c.is_ascii_digit()) {
+            // The second number given to submit is the part.
+            match (command, options.days.is_empty(), options.part) {
+                (Command::Submit, false, None) => options.part = Some(parse_part(arg)?),
+                (Command::Submit, false, Some(_)) => return Err(CliError::UnknownArgument(arg)),
+                _ => parse_days(&arg, &mut options.days)?,
+            }
+            continue;
+        }
+        let flag = FLAGS
+            .iter()
+            .find(|flag| flag.long == arg || flag.short == Some(arg.as_str()))
+            .ok_or(CliError::UnknownArgument(arg))?;
+        let name = flag.long;
+        // Parameters are the only flag that can be repeated.
+        if seen.contains(&name) && name != "--param" {
+            return Err(CliError::Duplicate(name));
+        }
+        seen.push(name);
+        if !flag.commands.contains(&command) {
+            return Err(CliError::Unsupported(name, command.name()));
+        }
+        let value = if flag.has_value {
+            args.next().ok_or(CliError::MissingValue(name))?
+        } else {
+            String::new()
+        };
+
+        match name {
+            "--part" => options.part = Some(parse_part(value)?),
+            "--input" => {
+                options.input = Some(match value.as_str() {
+                    "-" => InputSource::Stdin,
+                    _ => InputSource::File(value.into()),
+                })
+            }
+            "--input-dir" => options.input_dir = Some(value.into()),
+            "--session-file" => options.session_file = Some(value.into()),
+            "--variant" => {
+                options.variant = Some(match value.as_str() {
+                    "default" => None,
+                    _ => Some(parse_name(value)?),
+                })
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    "csv" => OutputFormat::Csv,
+                    _ => return Err(CliError::InvalidFormat(value)),
+                }
+            }
+            "--record" => options.record = true,
+            "--watch" => options.watch = true,
+            "--jobs" => {
+                options.jobs = match value.parse() {
+                    Ok(x) if x > 0 => Some(x),
+                    _ => return Err(CliError::InvalidNumber(value)),
+                }
+            }
+            "--timeout" => {
+                let timeout = parse_duration(&value).ok_or(CliError::InvalidDuration(value))?;
+                options.limits.timeout = Some(timeout);
+            }
+            "--memory-limit" => {
+                options.limits.memory =
+                    Some(parse_size(&value).ok_or(CliError::InvalidSize(value))?)
+            }
+            "--warmup" => {
+                options.bench.warmup =
+                    parse_duration(&value).ok_or(CliError::InvalidDuration(value))?
+            }
+            "--time" => {
+                options.bench.max_time =
+                    parse_duration(&value).ok_or(CliError::InvalidDuration(value))?
+            }
+            "--allocations" => options.bench.allocations = true,
+            "--iterations" => {
+                options.bench.max_iterations = match value.parse() {
+                    Ok(x) if x > 0 => x,
+                    _ => return Err(CliError::InvalidNumber(value)),
+                }
+            }
+            "--save-baseline" => options.baseline.save = Some(parse_name(value)?),
+            "--baseline" => options.baseline.compare = Some(parse_name(value)?),
+            "--threshold" => {
+                options.baseline.threshold = match value.trim_end_matches('%').parse() {
+                    Ok(x) if x >= 0.0 && f64::is_finite(x) => x,
+                    _ => return Err(CliError::InvalidNumber(value)),
+                }
+            }
+            "--history" => options.baseline.history = true,
+            "--refetch" => options.refetch = true,
+            "--write" => options.write = true,
+            "--verbose" => options.verbose = true,
+            "--scales" => {
+                options.scales = value
+                    .split(',')
+                    .map(|scale| scale.parse().ok().filter(|&scale| scale > 0))
+                    .collect::<Option<_>>()
+                    .ok_or(CliError::InvalidNumber(value))?
+            }
+            "--seed" => options.seed = value.parse().map_err(|_| CliError::InvalidNumber(value))?,
+            "--pick" => {
+                options.pick = value
+                    .split(',')
+                    .map(|nr| nr.parse().ok().filter(|&nr| nr > 0))
+                    .collect::<Option<_>>()
+                    .ok_or(CliError::InvalidNumber(value))?
+            }
+            "--param" => {
+                let (name, value) = match value.split_once('=') {
+                    Some((name, nr)) if !name.is_empty() => match nr.parse() {
+                        Ok(nr) => (name.to_owned(), nr),
+                        Err(_) => return Err(CliError::InvalidParam(value)),
+                    },
+                    _ => return Err(CliError::InvalidParam(value)),
+                };
+                if options.params.iter().any(|(other, _)| *other == name) {
+                    return Err(CliError::DuplicateParam(name));
+                }
+                options.params.push((name, value));
+            }
+            _ => unreachable!(),
+        }
+    }
+    options.days.sort_unstable();
+    options.days.dedup();
+
+    if command == Command::Submit && (options.days.len() != 1 || options.part.is_none()) {
+        return Err(CliError::SubmitUsage);
+    }
+    if options.input.is_some() && options.days.len() != 1 {
+        return Err(CliError::InputRequiresSingleDay);
+    }
+    if options.input.is_some() && options.record {
+        return Err(CliError::RecordWithInput);
+    }
+    if options.input.is_some() && options.variant.is_some() {
+        return Err(CliError::Conflict("--input", "--variant"));
+    }
+    if options.watch && options.record {
+        return Err(CliError::Conflict("--watch", "--record"));
+    }
+    if options.watch && options.input == Some(InputSource::Stdin) {
+        return Err(CliError::Conflict("--watch", "--input -"));
+    }
+    // Answers and timings of other parameters can't be compared to the stored ones.
+    if !options.params.is_empty() {
+        let conflict = [
+            ("--record", options.record),
+            ("--save-baseline", options.baseline.save.is_some()),
+            ("--baseline", options.baseline.compare.is_some()),
+            ("--history", options.baseline.history),
+        ]
+        .into_iter()
+        .find(|&(_, is_set)| is_set);
+        if let Some((flag, _)) = conflict {
+            return Err(CliError::Conflict("--param", flag));
+        }
+    }
+    if !options.scales.is_empty() {
+        let conflict = [
+            ("--input", options.input.is_some()),
+            ("--variant", options.variant.is_some()),
+            ("--save-baseline", options.baseline.save.is_some()),
+            ("--baseline", options.baseline.compare.is_some()),
+            ("--history", options.baseline.history),
+        ]
+        .into_iter()
+        .find(|&(_, is_set)| is_set);
+        if let Some((flag, _)) = conflict {
+            return Err(CliError::Conflict("--scales", flag));
+        }
+    }
+
+    Ok(Cli::Options(Box::new(options)))
+}
+
+fn parse_part(part: String) -> Result<Part, CliError> {
+    match part.as_str() {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(CliError::InvalidPart(part)),
+    }
+}
+
+fn parse_name(name: String) -> Result<String, CliError> {
+    let is_valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    if name.is_empty() || name.starts_with('.') || !name.chars().all(is_valid) {
+        return Err(CliError::InvalidName(name));
+    }
+    Ok(name)
+}
+
+fn parse_duration(str: &str) -> Option<Duration> {
+    let split = str
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(str.len());
+    let (value, unit) = str.split_at(split);
+    let value = value.parse::<f64>().ok()?;
+    let scale = match unit {
+        "ns" => 1e-9,
+        "us" | "µs" => 1e-6,
+        "ms" => 1e-3,
+        "s" | "" => 1.0,
+        "m" => 60.0,
+        _ => return None,
+    };
+    let secs = value * scale;
+    (secs < u32::MAX as f64).then(|| Duration::from_secs_f64(secs))
+}
+
+fn parse_size(str: &str) -> Option<usize> {
+    let split = str
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(str.len());
+    let (value, unit) = str.split_at(split);
+    let value = value.parse::<f64>().ok()?;
+    let scale = match unit.trim_end_matches("iB").trim_end_matches('B') {
+        "" => 1u64,
+        "K" | "k" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return None,
+    };
+    let bytes = value * scale as f64;
+    if bytes >= 1.0 && bytes < usize::MAX as f64 {
+        Some(bytes as usize)
+    } else {
+        None
+    }
+}
+
+fn parse_days(arg: &str, days: &mut Vec<u32>) -> Result<(), CliError> {
+    let invalid = || CliError::InvalidDays(arg.to_owned());
+    let parse_day = |s: &str| -> Result<u32, CliError> {
+        let day = s.parse::<u32>().map_err(|_| invalid())?;
+        if !(1..=25).contains(&day) {
+            return Err(CliError::DayOutOfRange(day));
+        }
+        Ok(day)
+    };
+
+    for item in arg.split(',') {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                if start > end {
+                    return Err(invalid());
+                }
+                days.extend(start..=end);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        parse_args(args.iter().map(|&s| s.to_owned()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse(args) {
+            Ok(Cli::Options(options)) => *options,
+            x => panic!("expected options, got: {x:?}"),
+        }
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(Command::Run, options(&[]).command);
+        assert_eq!(Command::Run, options(&["run"]).command);
+        assert_eq!(Command::Bench, options(&["bench"]).command);
+        assert_eq!(Command::List, options(&["list"]).command);
+        assert_eq!(Command::History, options(&["history", "19"]).command);
+        let opts = options(&["inputs", "verify", "3-5", "--refetch"]);
+        assert_eq!((Command::VerifyInputs, true), (opts.command, opts.refetch));
+        assert_eq!(
+            Command::EncryptInputs,
+            options(&["inputs", "encrypt"]).command
+        );
+        let opts = options(&["submit", "5", "2"]);
+        assert_eq!(
+            (Command::Submit, vec![5], Some(Part::Two)),
+            (opts.command, opts.days, opts.part)
+        );
+        assert_eq!(Err(CliError::SubmitUsage), parse(&["submit", "5"]));
+        let opts = options(&["examples", "12", "--write", "--pick", "1,3"]);
+        assert_eq!(
+            (Command::Examples, true, vec![1, 3]),
+            (opts.command, opts.write, opts.pick)
+        );
+        assert_eq!(
+            Err(CliError::InvalidNumber("0".to_owned())),
+            parse(&["examples", "--pick", "0"])
+        );
+        assert_eq!(Err(CliError::SubmitUsage), parse(&["submit", "5-6", "1"]));
+        assert_eq!(
+            Err(CliError::InvalidPart("3".to_owned())),
+            parse(&["submit", "5", "3"])
+        );
+        assert_eq!(
+            Err(CliError::UnknownArgument("1".to_owned())),
+            parse(&["submit", "5", "2", "1"])
+        );
+        assert_eq!(
+            Err(CliError::UnknownSubcommand("check".to_owned())),
+            parse(&["inputs", "check"])
+        );
+        assert_eq!(
+            Err(CliError::Unsupported("--refetch", "run")),
+            parse(&["--refetch"])
+        );
+        assert_eq!(Ok(Cli::Help), parse(&["bench", "--help"]));
+        assert_eq!(Ok(Cli::Help), parse(&["-h"]));
+    }
+
+    #[test]
+    fn day_selection() {
+        assert_eq!(Vec::<u32>::new(), options(&[]).days);
+        assert_eq!(vec![5], options(&["5"]).days);
+        assert_eq!(vec![3, 4, 5, 6, 7], options(&["3-7"]).days);
+        assert_eq!(vec![1, 4, 9], options(&["9,1,4"]).days);
+        assert_eq!(vec![1, 2, 3, 10, 20], options(&["1-3,10", "20", "2"]).days);
+        assert_eq!(Err(CliError::DayOutOfRange(26)), parse(&["26"]));
+        assert_eq!(Err(CliError::DayOutOfRange(0)), parse(&["0-3"]));
+        assert_eq!(Err(CliError::InvalidDays("7-3".to_owned())), parse(&["7-3"]));
+        assert_eq!(Err(CliError::InvalidDays("1,,2".to_owned())), parse(&["1,,2"]));
+    }
+
+    #[test]
+    fn options_and_errors() {
+        let opts = options(&["bench", "12", "--part", "2", "-i", "-"]);
+        assert_eq!(Command::Bench, opts.command);
+        assert_eq!(Some(Part::Two), opts.part);
+        assert_eq!(Some(InputSource::Stdin), opts.input);
+        assert_eq!(OutputFormat::Text, opts.format);
+        assert_eq!(OutputFormat::Csv, options(&["--format", "csv"]).format);
+        assert_eq!(OutputFormat::Json, options(&["list", "-f", "json"]).format);
+        assert_eq!(None, opts.jobs);
+        assert_eq!(Some(8), options(&["-j", "8"]).jobs);
+        assert_eq!(
+            Err(CliError::InvalidNumber("0".to_owned())),
+            parse(&["--jobs", "0"])
+        );
+
+        assert_eq!(
+            Some(InputSource::File("foo.txt".into())),
+            options(&["3", "--input", "foo.txt"]).input
+        );
+        let opts = options(&["--input-dir", "../in", "--session-file", "key"]);
+        assert_eq!(Some(PathBuf::from("../in")), opts.input_dir);
+        assert_eq!(Some(PathBuf::from("key")), opts.session_file);
+        assert_eq!(Err(CliError::InvalidPart("3".to_owned())), parse(&["-p", "3"]));
+        assert_eq!(Err(CliError::MissingValue("--part")), parse(&["--part"]));
+        assert_eq!(Err(CliError::InvalidFormat("xml".to_owned())), parse(&["-f", "xml"]));
+        assert_eq!(Err(CliError::Duplicate("--part")), parse(&["-p", "1", "-p", "2"]));
+        assert_eq!(Err(CliError::InputRequiresSingleDay), parse(&["-i", "-"]));
+        assert_eq!(Err(CliError::InputRequiresSingleDay), parse(&["1,2", "-i", "-"]));
+        assert_eq!(Err(CliError::UnknownArgument("--bench".to_owned())), parse(&["--bench"]));
+        assert_eq!(Err(CliError::UnknownArgument("foo".to_owned())), parse(&["foo"]));
+        assert_eq!(Err(CliError::Unsupported("--part", "list")), parse(&["list", "-p", "1"]));
+        assert_eq!(
+            Err(CliError::Unsupported("--record", "bench")),
+            parse(&["bench", "-r"])
+        );
+        assert_eq!(
+            Err(CliError::RecordWithInput),
+            parse(&["1", "-r", "-i", "-"])
+        );
+        assert!(options(&["1-5", "--record"]).record);
+        assert!(options(&["15", "-w", "-i", "alt.txt"]).watch);
+        assert_eq!(None, opts.variant);
+        assert_eq!(Some(None), options(&["-v", "default"]).variant);
+        let variant = options(&["bench", "--variant", "stress"]).variant;
+        assert_eq!(Some(Some("stress".to_owned())), variant);
+        assert_eq!(
+            Err(CliError::Conflict("--input", "--variant")),
+            parse(&["1", "-i", "x.txt", "-v", "alt"])
+        );
+        assert_eq!(
+            Err(CliError::Conflict("--watch", "--record")),
+            parse(&["-w", "-r"])
+        );
+        assert_eq!(
+            Err(CliError::Conflict("--watch", "--input -")),
+            parse(&["15", "--watch", "-i", "-"])
+        );
+        assert_eq!(
+            Err(CliError::Unsupported("--time", "run")),
+            parse(&["--time", "1s"])
+        );
+    }
+
+    #[test]
+    fn bench_config() {
+        assert_eq!(BenchConfig::default(), options(&["bench"]).bench);
+        let config = options(&[
+            "bench",
+            "--warmup",
+            "0",
+            "--time",
+            "1.5s",
+            "--iterations",
+            "20",
+            "--allocations",
+        ]);
+        assert_eq!(Duration::ZERO, config.bench.warmup);
+        assert_eq!(Duration::from_millis(1500), config.bench.max_time);
+        assert_eq!(20, config.bench.max_iterations);
+        assert!(config.bench.allocations);
+
+        assert_eq!(Some(Duration::from_micros(250)), parse_duration("250us"));
+        assert_eq!(Some(Duration::from_secs(120)), parse_duration("2m"));
+        assert_eq!(None, parse_duration("5 s"));
+        assert_eq!(None, parse_duration("-5s"));
+        assert_eq!(Some(1000), parse_size("1000"));
+        assert_eq!(Some(512 << 20), parse_size("512M"));
+        assert_eq!(Some(3 << 29), parse_size("1.5GiB"));
+        assert_eq!(Some(4 << 10), parse_size("4kB"));
+        assert_eq!(None, parse_size("0"));
+        assert_eq!(None, parse_size("12 MB"));
+        let limits = options(&["--timeout", "10s", "--memory-limit", "1G"]).limits;
+        assert_eq!(Some(Duration::from_secs(10)), limits.timeout);
+        assert_eq!(Some(1 << 30), limits.memory);
+        let invalid = |s: &str| CliError::InvalidNumber(s.to_owned());
+        assert_eq!(Err(invalid("0")), parse(&["bench", "--iterations", "0"]));
+        assert_eq!(
+            Err(CliError::Duplicate("--warmup")),
+            parse(&["bench", "--warmup", "1s", "--warmup", "2s"])
+        );
+
+        let sweep = options(&["bench", "22", "--scales", "1,2,4", "--seed", "7"]);
+        assert_eq!((vec![1, 2, 4], 7), (sweep.scales, sweep.seed));
+        assert_eq!(Err(invalid("1,0")), parse(&["bench", "--scales", "1,0"]));
+        assert_eq!(
+            Err(CliError::Conflict("--scales", "--baseline")),
+            parse(&["bench", "--scales", "1", "--baseline", "main"])
+        );
+
+        let params = options(&["14", "--param", "pt1_steps=20", "--param", "pt2_steps=-1"]);
+        assert_eq!(
+            vec![("pt1_steps".to_owned(), 20), ("pt2_steps".to_owned(), -1)],
+            params.params
+        );
+        let invalid = |s: &str| CliError::InvalidParam(s.to_owned());
+        assert_eq!(Err(invalid("steps")), parse(&["--param", "steps"]));
+        assert_eq!(Err(invalid("=5")), parse(&["--param", "=5"]));
+        assert_eq!(Err(invalid("steps=x")), parse(&["--param", "steps=x"]));
+        assert_eq!(
+            Err(CliError::DuplicateParam("steps".to_owned())),
+            parse(&["--param", "steps=1", "--param", "steps=2"])
+        );
+        assert_eq!(
+            Err(CliError::Conflict("--param", "--record")),
+            parse(&["--param", "steps=1", "--record"])
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use crate::cli::Options;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "aoc.conf";
+
+/// A configured value, together with a description of where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: String,
+}
+
+impl<T> Setting<T> {
+    fn new(value: T, source: impl Into<String>) -> Setting<T> {
+        Setting {
+            value,
+            source: source.into(),
+        }
+    }
+}
+
+/// A secret, given either directly or as a file containing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Secret {
+    Value(String),
+    File(PathBuf),
+}
+
+/// Locations of the inputs, the session key and the key used to encrypt the
+/// inputs. Each is taken from the first of: the command line, the environment
+/// (`AOC_INPUT_DIR`, `AOC_SESSION`, `AOC_SESSION_FILE`, `AOC_INPUT_KEY` and
+/// `AOC_INPUT_KEY_FILE`), the config file, and finally the crate root.
+///
+/// An input directory that is configured, rather than the one in the crate
+/// root, may be shared by several years, so the inputs are stored in a
+/// subdirectory named after the year.
+///
+/// The config file is `aoc.conf` in the crate root, or `$AOC_CONFIG`, and
+/// holds `key = value` lines, with `input_dir`, `session_file` and `key_file`
+/// as keys. Relative paths in it are relative to the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Year of the event, which the inputs are downloaded for.
+    pub year: u32,
+    pub input_dir: Setting<PathBuf>,
+    /// Places to look for the session key, in order of precedence.
+    pub session: Vec<Setting<Secret>>,
+    /// Places to look for the input encryption key, in order of precedence.
+    /// The last one is the default, `input_key.txt` in the crate root.
+    pub input_key: Vec<Setting<Secret>>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ConfigFile {
+    input_dir: Option<PathBuf>,
+    session_file: Option<PathBuf>,
+    key_file: Option<PathBuf>,
+}
+
+impl ConfigFile {
+    fn load(path: &Path) -> Result<ConfigFile> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::parse(path.parent().unwrap_or(Path::new("")), &contents)
+                .with_context(|| format!("invalid config file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ConfigFile::default()),
+            Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    fn parse(dir: &Path, contents: &str) -> Result<ConfigFile> {
+        let mut config = ConfigFile::default();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("line {}: expected `key = value`", i + 1))?;
+            let value = Some(dir.join(value.trim()));
+            match key.trim() {
+                "input_dir" => config.input_dir = value,
+                "session_file" => config.session_file = value,
+                "key_file" => config.key_file = value,
+                key => return Err(anyhow!("line {}: unknown key {key}", i + 1)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Config {
+    pub fn new(root: &Path, year: u32, options: &Options) -> Result<Config> {
+        Self::resolve(root, year, options, |name| std::env::var(name).ok())
+    }
+
+    fn resolve(
+        root: &Path,
+        year: u32,
+        options: &Options,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config> {
+        let env = |name: &str| env(name).filter(|value| !value.trim().is_empty());
+        let config_path = match env("AOC_CONFIG") {
+            Some(path) => PathBuf::from(path),
+            None => root.join(CONFIG_FILE),
+        };
+        let file = ConfigFile::load(&config_path)?;
+        let from_file = || format!("input_dir in {}", config_path.display());
+
+        let input_dir = if let Some(dir) = &options.input_dir {
+            Setting::new(dir.join(year.to_string()), "--input-dir")
+        } else if let Some(dir) = env("AOC_INPUT_DIR") {
+            Setting::new(Path::new(&dir).join(year.to_string()), "AOC_INPUT_DIR")
+        } else if let Some(dir) = file.input_dir {
+            Setting::new(dir.join(year.to_string()), from_file())
+        } else {
+            Setting::new(root.join("inputs"), "crate root")
+        };
+
+        let mut session = Vec::new();
+        if let Some(path) = &options.session_file {
+            session.push(Setting::new(Secret::File(path.clone()), "--session-file"));
+        }
+        if let Some(key) = env("AOC_SESSION") {
+            session.push(Setting::new(Secret::Value(key), "AOC_SESSION"));
+        }
+        if let Some(path) = env("AOC_SESSION_FILE") {
+            let key = Secret::File(path.into());
+            session.push(Setting::new(key, "AOC_SESSION_FILE"));
+        }
+        if let Some(path) = file.session_file {
+            let source = format!("session_file in {}", config_path.display());
+            session.push(Setting::new(Secret::File(path), source));
+        }
+        let path = root.join("session_key.txt");
+        session.push(Setting::new(Secret::File(path), "crate root"));
+
+        let mut input_key = Vec::new();
+        if let Some(key) = env("AOC_INPUT_KEY") {
+            input_key.push(Setting::new(Secret::Value(key), "AOC_INPUT_KEY"));
+        }
+        if let Some(path) = env("AOC_INPUT_KEY_FILE") {
+            let key = Secret::File(path.into());
+            input_key.push(Setting::new(key, "AOC_INPUT_KEY_FILE"));
+        }
+        if let Some(path) = file.key_file {
+            let source = format!("key_file in {}", config_path.display());
+            input_key.push(Setting::new(Secret::File(path), source));
+        }
+        let path = root.join("input_key.txt");
+        input_key.push(Setting::new(Secret::File(path), "crate root"));
+
+        Ok(Config {
+            year,
+            input_dir,
+            session,
+            input_key,
+        })
+    }
+
+    /// Returns the first session key that can be found, or an error listing
+    /// all the places that were tried.
+    pub fn session_key(&self) -> Result<String> {
+        find_secret(&self.session, "session key")
+    }
+
+    /// Returns the first input encryption key that can be found, or an error
+    /// listing all the places that were tried.
+    pub fn input_key(&self) -> Result<String> {
+        find_secret(&self.input_key, "input key")
+    }
+
+    /// Whether inputs should be encrypted: an input key is configured
+    /// somewhere, or the default key file exists. The key itself may still be
+    /// missing or unreadable.
+    pub fn has_input_key(&self) -> bool {
+        match self.input_key.as_slice() {
+            [] => false,
+            [Setting {
+                value: Secret::File(path),
+                ..
+            }] => path.exists(),
+            _ => true,
+        }
+    }
+}
+
+fn find_secret(settings: &[Setting<Secret>], name: &str) -> Result<String> {
+    let mut tried = Vec::new();
+    for Setting { value, source } in settings {
+        let path = match value {
+            Secret::Value(key) => return Ok(key.trim().to_owned()),
+            Secret::File(path) => path,
+        };
+        let reason = match std::fs::read_to_string(path) {
+            Ok(key) if !key.trim().is_empty() => return Ok(key.trim().to_owned()),
+            Ok(_) => "file is empty".to_owned(),
+            Err(e) => e.to_string(),
+        };
+        tried.push(format!("{} (from {source}): {reason}", path.display()));
+    }
+    Err(anyhow!(
+        "no {name} found, tried:\n    {}",
+        tried.join("\n    ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{parse_args, Cli};
+
+    fn options(args: &[&str]) -> Options {
+        match parse_args(args.iter().map(|&s| s.to_owned())) {
+            Ok(Cli::Options(options)) => *options,
+            x => panic!("expected options, got: {x:?}"),
+        }
+    }
+
+    #[test]
+    fn config_file() {
+        let contents =
+            "# comment\n\ninput_dir = ../inputs\n session_file=/secret/key.txt \nkey_file = key\n";
+        assert_eq!(
+            ConfigFile {
+                input_dir: Some("conf/../inputs".into()),
+                session_file: Some("/secret/key.txt".into()),
+                key_file: Some("conf/key".into()),
+            },
+            ConfigFile::parse(Path::new("conf"), contents).unwrap()
+        );
+        assert!(ConfigFile::parse(Path::new(""), "input_dir").is_err());
+        assert!(ConfigFile::parse(Path::new(""), "inputs = x").is_err());
+    }
+
+    #[test]
+    fn precedence() {
+        let root = Path::new("/nonexistent/root");
+        let resolve = |args: &[&str], vars: &[(&str, &str)]| {
+            let env = |name: &str| {
+                let var = vars.iter().find(|(key, _)| *key == name);
+                var.map(|(_, value)| value.to_string())
+            };
+            Config::resolve(root, 2021, &options(args), env).unwrap()
+        };
+
+        let config = resolve(&[], &[]);
+        assert_eq!(root.join("inputs"), config.input_dir.value);
+        assert_eq!(
+            vec![Secret::File(root.join("session_key.txt"))],
+            config
+                .session
+                .into_iter()
+                .map(|s| s.value)
+                .collect::<Vec<_>>()
+        );
+
+        let vars = [("AOC_INPUT_DIR", "env_inputs"), ("AOC_SESSION", "abc")];
+        let config = resolve(&[], &vars);
+        assert_eq!(PathBuf::from("env_inputs/2021"), config.input_dir.value);
+        assert_eq!("AOC_INPUT_DIR", config.input_dir.source);
+        assert_eq!(Some("abc".to_owned()), config.session_key().ok());
+
+        let config = resolve(&["--input-dir", "cli", "--session-file", "key"], &vars);
+        assert_eq!(PathBuf::from("cli/2021"), config.input_dir.value);
+        assert_eq!(
+            vec!["--session-file", "AOC_SESSION", "crate root"],
+            config.session.iter().map(|s| &s.source).collect::<Vec<_>>()
+        );
+
+        let error = resolve(&[], &[("AOC_SESSION", " ")])
+            .session_key()
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("session_key.txt (from crate root)"));
+    }
+}
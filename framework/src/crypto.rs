@@ -0,0 +1,101 @@
+use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use std::num::NonZeroU32;
+use thiserror::Error;
+
+/// Identifies encrypted inputs, and the version of the format.
+const MAGIC: &[u8] = b"aoc-input-v1\n";
+const SALT: &[u8] = b"advent_of_code_2021 inputs";
+const ITERATIONS: u32 = 100_000;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    #[error("not an encrypted input")]
+    InvalidFormat,
+    #[error("decryption failed, the key is wrong or the file is corrupted")]
+    Decryption,
+    #[error("failed to generate a nonce")]
+    Random,
+}
+
+/// Key used to encrypt inputs at rest, derived from a passphrase. Encrypted
+/// files consist of a header, a random nonce, and the input encrypted with
+/// ChaCha20-Poly1305.
+pub struct Key(LessSafeKey);
+
+impl Key {
+    pub fn derive(passphrase: &str) -> Key {
+        let mut bytes = [0; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(ITERATIONS).unwrap(),
+            SALT,
+            passphrase.as_bytes(),
+            &mut bytes,
+        );
+        let key = UnboundKey::new(&aead::CHACHA20_POLY1305, &bytes).unwrap();
+        Key(LessSafeKey::new(key))
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| CryptoError::Random)?;
+        let mut data = plaintext.to_vec();
+        self.0
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+            .map_err(|_| CryptoError::Random)?;
+        Ok([MAGIC, &nonce, &data].concat())
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let data = data.strip_prefix(MAGIC).ok_or(CryptoError::InvalidFormat)?;
+        if data.len() < NONCE_LEN {
+            return Err(CryptoError::InvalidFormat);
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| CryptoError::InvalidFormat)?;
+        let mut data = ciphertext.to_vec();
+        let len = self
+            .0
+            .open_in_place(nonce, Aad::empty(), &mut data)
+            .map_err(|_| CryptoError::Decryption)?
+            .len();
+        data.truncate(len);
+        Ok(data)
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encryption() {
+        let key = Key::derive("correct horse battery staple");
+        let input = b"forward 5\ndown 5\nforward 8\n";
+        let encrypted = key.encrypt(input).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.windows(input.len()).any(|w| w == input));
+        assert_ne!(encrypted, key.encrypt(input).unwrap());
+        assert_eq!(Ok(input.to_vec()), key.decrypt(&encrypted));
+        assert_eq!(Ok(Vec::new()), key.decrypt(&key.encrypt(b"").unwrap()));
+
+        let wrong_key = Key::derive("correct horse battery stapler");
+        assert_eq!(Err(CryptoError::Decryption), wrong_key.decrypt(&encrypted));
+        let mut corrupted = encrypted.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert_eq!(Err(CryptoError::Decryption), key.decrypt(&corrupted));
+        assert_eq!(Err(CryptoError::InvalidFormat), key.decrypt(input));
+        assert_eq!(Err(CryptoError::InvalidFormat), key.decrypt(MAGIC));
+    }
+}
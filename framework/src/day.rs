@@ -1,27 +1,48 @@
-use crate::parsers::{error::Finish, ParseResult};
+use crate::{
+    allocator::{count_allocations, AllocStats},
+    normalize::Normalization,
+    params::Param,
+    parsers::{error::Finish, ParseResult},
+    sandbox::Failure,
+};
 use anyhow::Result;
-use colored::Colorize;
 use std::{
     borrow::Borrow,
     fmt::Display,
+    hint::black_box,
     marker::PhantomData,
     time::{Duration, Instant},
 };
 
+/// Declares a day as `day!(nr, parse => pt1, pt2)`, or with a stage shared by
+/// both parts as `day!(nr, parse => prepare => pt1, pt2)`.
 #[macro_export]
 macro_rules! day {
-    ($nr:literal, $parser:expr => $pt1:expr, $pt2:expr) => {
+    (@common $nr:literal, $parser:expr, $prepare:expr, $has_prepare:literal, $pt1:expr, $pt2:expr $(, $option:ident = $value:expr)*) => {
         pub fn day() -> impl Day {
             $crate::day::DayCommon {
                 nr: $nr,
                 parser: $parser,
+                prepare: $prepare,
+                has_prepare: $has_prepare,
                 pt1: $pt1,
                 pt2: $pt2,
+                normalization: $crate::normalize::Normalization::ALL,
+                validator: None,
+                generator: None,
+                params: &[],
                 phantom1: ::std::marker::PhantomData,
                 phantom2: ::std::marker::PhantomData,
             }
+            $(.$option($value))*
         }
     };
+    ($nr:literal, $parser:expr => $prepare:expr => $pt1:expr, $pt2:expr $(, $option:ident = $value:expr)* $(,)?) => {
+        $crate::day!(@common $nr, $parser, $prepare, true, $pt1, $pt2 $(, $option = $value)*);
+    };
+    ($nr:literal, $parser:expr => $pt1:expr, $pt2:expr $(, $option:ident = $value:expr)* $(,)?) => {
+        $crate::day!(@common $nr, $parser, ::std::convert::identity, false, $pt1, $pt2 $(, $option = $value)*);
+    };
 }
 
 #[macro_export]
@@ -30,50 +51,144 @@ macro_rules! tests {
         #[cfg(test)]
         mod tests {
             use super::*;
-            use $crate::simple_tests;
+            #[allow(unused_imports)]
+            use $crate::{generator_tests, simple_tests};
 
             $($x)*
         }
     };
 }
 
+/// Tests a part against examples, optionally with parameters set, e.g.
+/// `simple_tests!(parse, pt1, pt1_tests, params(steps = 5), EXAMPLE => 3)`.
 #[macro_export]
 macro_rules! simple_tests {
-    ($parse:expr, $pt:expr, $pt_name:ident, $($input:expr => $expected:expr),+$(,)*) => {
+    (@test $parse:expr, $prepare:expr, $pt:expr, $pt_name:ident, [$($param:ident = $value:expr),*], $($input:expr => $expected:expr),+) => {
         #[test]
         fn $pt_name() -> ::anyhow::Result<()> {
-            $({
-                let input = $crate::parsers::error::Finish::finish($parse($input))?;
-                let result = $crate::day::ToResult::to_result($pt(&input))?;
-                let expected = $expected;
-                if result != expected {
-                    return Err(anyhow::anyhow!("Expected: {expected}, but got: {result}"));
-                }
-            })+
+            let overrides: &[(&str, i64)] = &[$((stringify!($param), $value)),*];
+            let params = $crate::day::Day::params(&day());
+            $crate::params::with(params, overrides, || {
+                $({
+                    let input = $crate::parsers::error::Finish::finish($parse($input))?;
+                    let prepared = $prepare(input);
+                    let result = $crate::day::ToResult::to_result($crate::day::PartFn::run(&$pt, &prepared))?;
+                    let expected = $expected;
+                    if result != expected {
+                        return Err(anyhow::anyhow!("Expected: {expected}, but got: {result}"));
+                    }
+                })+
+                Ok(())
+            })
+        }
+    };
+    ($parse:expr => $prepare:expr, $pt:expr, $pt_name:ident, params($($param:ident = $value:expr),*), $($input:expr => $expected:expr),+$(,)*) => {
+        $crate::simple_tests!(@test $parse, $prepare, $pt, $pt_name, [$($param = $value),*], $($input => $expected),+);
+    };
+    ($parse:expr => $prepare:expr, $pt:expr, $pt_name:ident, $($input:expr => $expected:expr),+$(,)*) => {
+        $crate::simple_tests!(@test $parse, $prepare, $pt, $pt_name, [], $($input => $expected),+);
+    };
+    ($parse:expr, $pt:expr, $pt_name:ident, params($($param:ident = $value:expr),*), $($input:expr => $expected:expr),+$(,)*) => {
+        $crate::simple_tests!(@test $parse, ::std::convert::identity, $pt, $pt_name, [$($param = $value),*], $($input => $expected),+);
+    };
+    ($parse:expr, $pt:expr, $pt_name:ident, $($input:expr => $expected:expr),+$(,)*) => {
+        $crate::simple_tests!(@test $parse, ::std::convert::identity, $pt, $pt_name, [], $($input => $expected),+);
+    };
+}
+
+/// Checks that a generator is deterministic, and that its inputs parse.
+#[macro_export]
+macro_rules! generator_tests {
+    ($generate:expr, $parse:expr) => {
+        #[test]
+        fn generator_tests() -> ::anyhow::Result<()> {
+            let (small, large) = ($generate(1, 1), $generate(1, 2));
+            assert_eq!(small, $generate(1, 1));
+            assert_ne!(small, $generate(2, 1));
+            assert!(large.len() > small.len());
+            for input in [small, large] {
+                $crate::parsers::error::Finish::finish($parse(&input))?;
+            }
             Ok(())
         }
     };
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// Whether this part is included in a selection, where `None` selects
+    /// both parts.
+    pub fn is_selected(self, selection: Option<Part>) -> bool {
+        selection.is_none() || selection == Some(self)
+    }
+}
+
 pub enum DayResult {
     NoInput(anyhow::Error),
+    /// The input was rejected by the validator of the day.
+    Rejected(anyhow::Error),
     ParseFailed(anyhow::Error),
+    /// The day panicked, or exceeded one of its limits.
+    Aborted(Failure),
     Ran {
-        pt1: Result<ColoredOutput>,
-        pt2: Result<ColoredOutput>,
+        pt1: Option<Result<Answer>>,
+        pt2: Option<Result<Answer>>,
     },
 }
 
+impl DayResult {
+    pub fn is_success(&self) -> bool {
+        match self {
+            DayResult::NoInput(_)
+            | DayResult::Rejected(_)
+            | DayResult::ParseFailed(_)
+            | DayResult::Aborted(_) => false,
+            DayResult::Ran { pt1, pt2 } => {
+                !matches!(pt1, Some(Err(_))) && !matches!(pt2, Some(Err(_)))
+            }
+        }
+    }
+}
+
+pub struct BenchPart {
+    pub duration: Duration,
+    pub allocs: Option<AllocStats>,
+    pub output: Result<Answer>,
+}
+
 pub struct BenchOutputs {
     pub parse: Duration,
-    pub pt1: Duration,
-    pub pt2: Duration,
+    pub parse_allocs: Option<AllocStats>,
+    /// Duration and allocations of the shared stage, if the day has one.
+    pub prepare: Option<(Duration, Option<AllocStats>)>,
+    pub pt1: Option<BenchPart>,
+    pub pt2: Option<BenchPart>,
 }
 
-pub trait Day {
+pub trait Day: Send + Sync {
     fn nr(&self) -> u32;
-    fn exec(&self, input: &[u8]) -> DayResult;
-    fn exec_bench(&self, input: &[u8]) -> Result<BenchOutputs>;
+    /// How the input is normalized before it's validated and parsed.
+    fn normalization(&self) -> Normalization;
+    /// Checks the (normalized) input before it's parsed, for inputs that the
+    /// parser would accept, but the solution can't handle.
+    fn validate(&self, input: &[u8]) -> Result<()>;
+    /// Generates a random input shaped like the real ones, where a `scale` of
+    /// 1 is about the size of a real input. `None` when the day has no
+    /// generator.
+    fn generate_input(&self, seed: u64, scale: u32) -> Option<Vec<u8>>;
+    /// Whether the parts share a stage that runs after parsing, which is
+    /// benchmarked as its own phase.
+    fn has_prepare(&self) -> bool;
+    /// The parameters of the day, which [`crate::params::with`] sets while
+    /// it runs.
+    fn params(&self) -> &'static [Param];
+    fn exec(&self, input: &[u8], part: Option<Part>) -> DayResult;
+    fn exec_bench(&self, input: &[u8], part: Option<Part>) -> Result<BenchOutputs>;
 }
 
 pub auto trait IsNotResult {}
@@ -97,88 +212,362 @@ impl<T: IsNotResult> ToResult for T {
     }
 }
 
-pub auto trait AutoImplementToColoredString {}
+/// The answer of a part, kept as a value until it is rendered, see
+/// [`crate::render`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// Text of multiple lines, such as a drawing of letters.
+    Block(String),
+    /// A result shown together with how it was derived, e.g. `3 × 4 = 12`,
+    /// where only the result is the actual answer.
+    Derived {
+        terms: Vec<Term>,
+        result: Box<Answer>,
+    },
+}
 
-pub trait ToColoredString {
-    fn to_colored(self) -> ColoredOutput;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Value(String),
+    Symbol(&'static str),
 }
 
-impl<T: Display + AutoImplementToColoredString> ToColoredString for T {
-    fn to_colored(self) -> ColoredOutput {
-        ColoredOutput {
-            str: self.to_string().white().bold().to_string(),
-            control_char_count: 11,
+impl Answer {
+    /// The actual answer, without the derivation.
+    pub fn result(&self) -> &Answer {
+        match self {
+            Answer::Derived { result, .. } => result.result(),
+            answer => answer,
         }
     }
 }
 
-pub struct ColoredOutput {
-    pub str: String,
-    pub control_char_count: usize,
+pub auto trait AutoImplementToAnswer {}
+
+pub trait ToAnswer {
+    fn to_answer(self) -> Answer;
+}
+
+impl<T: Display + AutoImplementToAnswer> ToAnswer for T {
+    fn to_answer(self) -> Answer {
+        let text = self.to_string();
+        if text.contains('\n') {
+            Answer::Block(text)
+        } else {
+            Answer::Text(text)
+        }
+    }
+}
+
+macro_rules! impl_int_answer {
+    ($($t:ty),*) => {
+        $(
+            impl !AutoImplementToAnswer for $t {}
+            impl ToAnswer for $t {
+                fn to_answer(self) -> Answer {
+                    Answer::Int(self as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_int_answer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+// Strings contain bytes, which would otherwise opt them out.
+impl AutoImplementToAnswer for String {}
+impl AutoImplementToAnswer for &str {}
+
+/// Marks a part that borrows its input, see [`PartFn`].
+pub struct ByRef;
+/// Marks a part that takes its input by `&mut`, see [`PartFn`].
+pub struct ByMut;
+/// Marks a part that takes ownership of its input, see [`PartFn`].
+pub struct ByValue;
+
+/// A part that borrows the input as `&T`, or takes it as `&mut T` or `T`, in
+/// which case it gets a clone. The clone is made by `take`, so benchmarks
+/// don't time it, and it's dropped afterwards, by the caller. The last part to
+/// use the input gets it moved instead, with `run_owned`.
+pub trait PartFn<S, T: ?Sized, M> {
+    type Output;
+    /// The clone of the input, if the part needs one.
+    type Owned;
+    fn take(shared: &S) -> Self::Owned;
+    fn call(&self, shared: &S, owned: &mut Self::Owned) -> Self::Output;
+
+    fn run(&self, shared: &S) -> Self::Output {
+        let mut owned = Self::take(shared);
+        self.call(shared, &mut owned)
+    }
+
+    fn run_owned(&self, shared: S) -> Self::Output {
+        self.run(&shared)
+    }
+}
+
+impl<S: Borrow<T>, T: ?Sized, O, F: Fn(&T) -> O> PartFn<S, T, ByRef> for F {
+    type Output = O;
+    type Owned = ();
+    fn take(_: &S) {}
+    fn call(&self, shared: &S, _: &mut ()) -> O {
+        self(shared.borrow())
+    }
+}
+
+impl<S: Borrow<T> + Into<T>, T: Clone, O, F: Fn(&mut T) -> O> PartFn<S, T, ByMut> for F {
+    type Output = O;
+    type Owned = T;
+    fn take(shared: &S) -> T {
+        shared.borrow().clone()
+    }
+    fn call(&self, _: &S, owned: &mut T) -> O {
+        self(owned)
+    }
+    fn run_owned(&self, shared: S) -> O {
+        self(&mut shared.into())
+    }
+}
+
+impl<S: Borrow<T> + Into<T>, T: Clone, O, F: Fn(T) -> O> PartFn<S, T, ByValue> for F {
+    type Output = O;
+    type Owned = Option<T>;
+    fn take(shared: &S) -> Option<T> {
+        Some(shared.borrow().clone())
+    }
+    fn call(&self, _: &S, owned: &mut Option<T>) -> O {
+        self(owned.take().unwrap())
+    }
+    fn run_owned(&self, shared: S) -> O {
+        self(shared.into())
+    }
 }
 
-pub struct DayCommon<P, P1, P2, I, I1, I2, O1, O2>
+/// Rejects an input before it's parsed, see [`Day::validate`].
+pub type Validator = fn(&[u8]) -> Result<()>;
+
+/// Generates an input from a seed and a scale, see [`Day::generate_input`].
+pub type Generator = fn(u64, u32) -> Vec<u8>;
+
+pub struct DayCommon<P, R, P1, P2, I, S, I1, I2, O1, O2, M1, M2>
 where
     P: for<'s> Fn(&'s [u8]) -> ParseResult<'s, I>,
-    P1: Fn(&I1) -> O1,
-    P2: Fn(&I2) -> O2,
-    I: Borrow<I1> + Borrow<I2>,
+    R: Fn(I) -> S,
+    P1: PartFn<S, I1, M1, Output = O1>,
+    P2: PartFn<S, I2, M2, Output = O2>,
+    S: Borrow<I1> + Borrow<I2>,
     I1: ?Sized,
     I2: ?Sized,
-    O1: ToResult<Output: ToColoredString>,
-    O2: ToResult<Output: ToColoredString>,
+    O1: ToResult<Output: ToAnswer>,
+    O2: ToResult<Output: ToAnswer>,
 {
     pub nr: u32,
     pub parser: P,
+    /// Turns the parsed input into what both parts borrow.
+    pub prepare: R,
+    pub has_prepare: bool,
     pub pt1: P1,
     pub pt2: P2,
-    pub phantom1: PhantomData<I1>,
-    pub phantom2: PhantomData<I2>,
+    pub normalization: Normalization,
+    pub validator: Option<Validator>,
+    pub generator: Option<Generator>,
+    pub params: &'static [Param],
+    pub phantom1: PhantomData<fn(&I1) -> M1>,
+    pub phantom2: PhantomData<fn(&I2) -> M2>,
 }
 
-impl<P, P1, P2, I, I1, I2, O1, O2> Day for DayCommon<P, P1, P2, I, I1, I2, O1, O2>
+/// Options of `day!`, given as `name = value` after the parts.
+impl<P, R, P1, P2, I, S, I1, I2, O1, O2, M1, M2>
+    DayCommon<P, R, P1, P2, I, S, I1, I2, O1, O2, M1, M2>
 where
     P: for<'s> Fn(&'s [u8]) -> ParseResult<'s, I>,
-    P1: Fn(&I1) -> O1,
-    P2: Fn(&I2) -> O2,
-    I: Borrow<I1> + Borrow<I2>,
+    R: Fn(I) -> S,
+    P1: PartFn<S, I1, M1, Output = O1>,
+    P2: PartFn<S, I2, M2, Output = O2>,
+    S: Borrow<I1> + Borrow<I2>,
     I1: ?Sized,
     I2: ?Sized,
-    O1: ToResult<Output: ToColoredString>,
-    O2: ToResult<Output: ToColoredString>,
+    O1: ToResult<Output: ToAnswer>,
+    O2: ToResult<Output: ToAnswer>,
+{
+    /// Replaces the default normalization, which applies every step.
+    pub fn normalize(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    pub fn generator(mut self, generator: Generator) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    pub fn params(mut self, params: &'static [Param]) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+impl<P, R, P1, P2, I, S, I1, I2, O1, O2, M1, M2> Day
+    for DayCommon<P, R, P1, P2, I, S, I1, I2, O1, O2, M1, M2>
+where
+    P: for<'s> Fn(&'s [u8]) -> ParseResult<'s, I> + Send + Sync,
+    R: Fn(I) -> S + Send + Sync,
+    P1: PartFn<S, I1, M1, Output = O1> + Send + Sync,
+    P2: PartFn<S, I2, M2, Output = O2> + Send + Sync,
+    S: Borrow<I1> + Borrow<I2>,
+    I1: ?Sized,
+    I2: ?Sized,
+    O1: ToResult<Output: ToAnswer>,
+    O2: ToResult<Output: ToAnswer>,
 {
     fn nr(&self) -> u32 {
         self.nr
     }
 
-    fn exec(&self, input: &[u8]) -> DayResult {
+    fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    fn validate(&self, input: &[u8]) -> Result<()> {
+        self.validator.map_or(Ok(()), |validator| validator(input))
+    }
+
+    fn generate_input(&self, seed: u64, scale: u32) -> Option<Vec<u8>> {
+        self.generator.map(|generator| generator(seed, scale))
+    }
+
+    fn has_prepare(&self) -> bool {
+        self.has_prepare
+    }
+
+    fn params(&self) -> &'static [Param] {
+        self.params
+    }
+
+    fn exec(&self, input: &[u8], part: Option<Part>) -> DayResult {
+        if let Err(e) = self.validate(input) {
+            return DayResult::Rejected(e);
+        }
         let input = match (self.parser)(input).finish() {
             Ok(x) => x,
             Err(e) => return DayResult::ParseFailed(e),
         };
-        let pt1 = (self.pt1)(input.borrow());
-        let pt2 = (self.pt2)(input.borrow());
+        let input = (self.prepare)(input);
+        // The last part that runs gets the input, only an earlier one clones it.
+        let (pt1, pt2) = if Part::Two.is_selected(part) {
+            let pt1 = Part::One.is_selected(part).then(|| self.pt1.run(&input));
+            (pt1, Some(self.pt2.run_owned(input)))
+        } else {
+            (Some(self.pt1.run_owned(input)), None)
+        };
         DayResult::Ran {
-            pt1: pt1.to_result().map(|x| x.to_colored()),
-            pt2: pt2.to_result().map(|x| x.to_colored()),
+            pt1: pt1.map(|x| x.to_result().map(|x| x.to_answer())),
+            pt2: pt2.map(|x| x.to_result().map(|x| x.to_answer())),
         }
     }
 
-    fn exec_bench(&self, input: &[u8]) -> Result<BenchOutputs> {
+    fn exec_bench(&self, input: &[u8], part: Option<Part>) -> Result<BenchOutputs> {
         let start = Instant::now();
-        let parse_result = (self.parser)(input);
+        let (parse_result, parse_allocs) =
+            count_allocations(|| black_box((self.parser)(black_box(input))));
         let parse = Instant::now() - start;
 
         let input = parse_result.finish()?;
 
         let start = Instant::now();
-        (self.pt1)(input.borrow());
-        let pt1 = Instant::now() - start;
+        let (input, prepare_allocs) = count_allocations(|| black_box((self.prepare)(input)));
+        let prepare = self
+            .has_prepare
+            .then(|| (Instant::now() - start, prepare_allocs));
 
-        let start = Instant::now();
-        (self.pt2)(input.borrow());
-        let pt2 = Instant::now() - start;
+        let pt1 = Part::One.is_selected(part).then(|| {
+            let mut owned = P1::take(&input);
+            let start = Instant::now();
+            let (output, allocs) =
+                count_allocations(|| black_box(self.pt1.call(black_box(&input), &mut owned)));
+            let duration = Instant::now() - start;
+            drop(owned);
+            BenchPart {
+                duration,
+                allocs,
+                output: output.to_result().map(|x| x.to_answer()),
+            }
+        });
+
+        let pt2 = Part::Two.is_selected(part).then(|| {
+            let mut owned = P2::take(&input);
+            let start = Instant::now();
+            let (output, allocs) =
+                count_allocations(|| black_box(self.pt2.call(black_box(&input), &mut owned)));
+            let duration = Instant::now() - start;
+            drop(owned);
+            BenchPart {
+                duration,
+                allocs,
+                output: output.to_result().map(|x| x.to_answer()),
+            }
+        });
+
+        Ok(BenchOutputs {
+            parse,
+            parse_allocs,
+            prepare,
+            pt1,
+            pt2,
+        })
+    }
+}
 
-        Ok(BenchOutputs { parse, pt1, pt2 })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    struct Input(Vec<u8>);
+
+    impl Clone for Input {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::Relaxed);
+            Input(self.0.clone())
+        }
+    }
+
+    fn parse(input: &[u8]) -> ParseResult<'_, Input> {
+        Ok((Input(input.to_vec()), &[]))
+    }
+
+    fn sort(input: &mut Input) -> u8 {
+        input.0.sort_unstable();
+        input.0[0]
+    }
+
+    fn len(input: Input) -> usize {
+        input.0.len()
+    }
+
+    crate::day!(1, parse => sort, len);
+
+    #[test]
+    fn clones_only_for_earlier_parts() {
+        let answers = |result| match result {
+            DayResult::Ran { pt1, pt2 } => [pt1, pt2].map(|pt| pt.map(|x| x.unwrap())),
+            _ => panic!("the day didn't run"),
+        };
+        let int = |x| Some(Answer::Int(x));
+        let input = &[3, 1, 2];
+        assert_eq!([int(1), int(3)], answers(day().exec(input, None)));
+        assert_eq!(1, CLONES.load(Ordering::Relaxed));
+        assert_eq!([int(1), None], answers(day().exec(input, Some(Part::One))));
+        assert_eq!([None, int(3)], answers(day().exec(input, Some(Part::Two))));
+        assert_eq!(1, CLONES.load(Ordering::Relaxed));
     }
 }
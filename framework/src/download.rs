@@ -0,0 +1,297 @@
+use crate::http::{HttpClient, Response, USER_AGENT};
+use anyhow::{Context, Result};
+use std::{
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const MINIMUM_TIME_BETWEEN_DOWNLOADS: Duration = Duration::from_secs(3);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DownloadError {
+    #[error("the session key was rejected, it has probably expired")]
+    SessionExpired,
+    #[error("the puzzle is not unlocked yet")]
+    NotUnlocked,
+    #[error("server error (HTTP {0})")]
+    Server(u16),
+    #[error("unexpected response (HTTP {0}): {1}")]
+    Unexpected(u16, String),
+    #[error("request failed: {0}")]
+    Transport(String),
+}
+
+impl DownloadError {
+    fn is_retryable(&self) -> bool {
+        matches!(self, DownloadError::Server(_) | DownloadError::Transport(_))
+    }
+}
+
+/// Turns a response to an input request into the input, or the reason why it
+/// is not one.
+pub fn classify(response: Response) -> Result<Vec<u8>, DownloadError> {
+    let body = String::from_utf8_lossy(&response.body);
+    let summary = || {
+        let line = body.lines().find(|line| !line.trim().is_empty());
+        line.unwrap_or("empty response").chars().take(100).collect()
+    };
+    match response.status {
+        200 => Ok(response.body),
+        400 | 401 | 403 => Err(DownloadError::SessionExpired),
+        404 => Err(DownloadError::NotUnlocked),
+        status @ 500..=599 => Err(DownloadError::Server(status)),
+        status => Err(DownloadError::Unexpected(status, summary())),
+    }
+}
+
+/// Enforces a minimum time between requests, also across runs, by storing the
+/// time of the last request in a file.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl RateLimiter {
+    pub fn new(path: PathBuf, interval: Duration) -> RateLimiter {
+        RateLimiter { path, interval }
+    }
+
+    fn last_request(&self) -> Option<SystemTime> {
+        let millis = std::fs::read_to_string(&self.path)
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        Some(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    /// Time left until the next request is allowed.
+    pub fn remaining(&self) -> Duration {
+        match self.last_request() {
+            // When the clock went backwards, wait a full interval to be safe.
+            Some(last) => SystemTime::now()
+                .duration_since(last)
+                .map_or(self.interval, |elapsed| {
+                    self.interval.saturating_sub(elapsed)
+                }),
+            None => Duration::ZERO,
+        }
+    }
+
+    pub fn wait(&self) {
+        thread::sleep(self.remaining());
+    }
+
+    pub fn record(&self) -> Result<()> {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, millis.to_string())
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+/// Downloads inputs, retrying server and connection errors with exponential
+/// backoff.
+pub struct Downloader {
+    client: Box<dyn HttpClient>,
+    base_url: String,
+    rate_limiter: RateLimiter,
+    pub retries: u32,
+    /// Delay before the first retry, which doubles for every next retry.
+    pub backoff: Duration,
+}
+
+impl Downloader {
+    pub fn new(
+        client: Box<dyn HttpClient>,
+        base_url: &str,
+        rate_limiter: RateLimiter,
+    ) -> Downloader {
+        Downloader {
+            client,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            rate_limiter,
+            retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+
+    pub fn input_url(&self, year: u32, day: u32) -> String {
+        format!("{}/{year}/day/{day}/input", self.base_url)
+    }
+
+    pub fn puzzle_url(&self, year: u32, day: u32) -> String {
+        format!("{}/{year}/day/{day}", self.base_url)
+    }
+
+    /// Downloads the input of a day. Errors contain a [`DownloadError`] when
+    /// the request was made. The contents of the input are not checked.
+    pub fn input(&self, year: u32, day: u32, session_key: &str) -> Result<Vec<u8>> {
+        self.fetch(&self.input_url(year, day), session_key)
+    }
+
+    /// Downloads the description of a puzzle, which only includes the second
+    /// part once the first one is solved.
+    pub fn puzzle(&self, year: u32, day: u32, session_key: &str) -> Result<Vec<u8>> {
+        self.fetch(&self.puzzle_url(year, day), session_key)
+    }
+
+    fn fetch(&self, url: &str, session_key: &str) -> Result<Vec<u8>> {
+        let cookie = format!("session={session_key}");
+        let headers = [("Cookie", cookie.as_str()), ("User-Agent", USER_AGENT)];
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.wait();
+            let result = self.client.get(url, &headers);
+            self.rate_limiter.record()?;
+            let error = match result.map(classify) {
+                Ok(Ok(input)) => return Ok(input),
+                Ok(Err(e)) => e,
+                Err(e) => DownloadError::Transport(format!("{e:#}")),
+            };
+            if !error.is_retryable() || attempt == self.retries {
+                let error = anyhow::Error::from(error);
+                return Err(match attempt {
+                    0 => error,
+                    _ => error.context(format!("giving up after {} attempts", attempt + 1)),
+                });
+            }
+            thread::sleep(self.backoff * 2u32.pow(attempt));
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{stub_server, UreqClient};
+    use anyhow::anyhow;
+    use std::sync::{Arc, Mutex};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aoc-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn downloader(client: impl HttpClient + 'static, base_url: &str, name: &str) -> Downloader {
+        let rate_limiter = RateLimiter::new(temp_path(name), Duration::ZERO);
+        let mut downloader = Downloader::new(Box::new(client), base_url, rate_limiter);
+        downloader.backoff = Duration::from_millis(1);
+        downloader
+    }
+
+    /// A response, or `None` for a failed connection.
+    type StubResponse = Option<(u16, &'static str)>;
+
+    /// Replies with the given responses in order, and keeps track of the
+    /// requested URLs.
+    #[derive(Clone, Default)]
+    struct StubClient {
+        responses: Arc<Mutex<Vec<StubResponse>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpClient for StubClient {
+        fn get(&self, url: &str, _headers: &[(&str, &str)]) -> Result<Response> {
+            self.requests.lock().unwrap().push(url.to_owned());
+            match self.responses.lock().unwrap().remove(0) {
+                Some((status, body)) => Ok(Response {
+                    status,
+                    body: body.as_bytes().to_vec(),
+                }),
+                None => Err(anyhow!("connection refused")),
+            }
+        }
+
+        fn post(&self, _url: &str, _headers: &[(&str, &str)], _body: &[u8]) -> Result<Response> {
+            unreachable!("downloads only use GET requests")
+        }
+    }
+
+    fn download(responses: Vec<StubResponse>) -> (Result<Vec<u8>>, usize) {
+        let client = StubClient::default();
+        *client.responses.lock().unwrap() = responses;
+        let result = downloader(client.clone(), "stub", "classify").input(2021, 7, "key");
+        let requests = client.requests.lock().unwrap();
+        assert!(requests.iter().all(|url| url == "stub/2021/day/7/input"));
+        (result, requests.len())
+    }
+
+    #[test]
+    fn classification_and_retries() {
+        let error = |result: Result<Vec<u8>>| result.unwrap_err().downcast::<DownloadError>();
+
+        let (result, requests) = download(vec![Some((200, "1\n2\n"))]);
+        assert_eq!((b"1\n2\n".to_vec(), 1), (result.unwrap(), requests));
+
+        let (result, requests) = download(vec![Some((400, "Please log in"))]);
+        assert_eq!(
+            (DownloadError::SessionExpired, 1),
+            (error(result).unwrap(), requests)
+        );
+
+        let (result, requests) = download(vec![Some((404, "Please don't"))]);
+        assert_eq!(
+            (DownloadError::NotUnlocked, 1),
+            (error(result).unwrap(), requests)
+        );
+
+        let (result, _) = download(vec![Some((302, "\nFound"))]);
+        assert_eq!(
+            DownloadError::Unexpected(302, "Found".to_owned()),
+            error(result).unwrap()
+        );
+
+        let (result, requests) = download(vec![None, Some((502, "")), Some((200, "5"))]);
+        assert_eq!((b"5".to_vec(), 3), (result.unwrap(), requests));
+
+        let (result, requests) = download(vec![Some((503, "")); 4]);
+        assert_eq!(
+            (DownloadError::Server(503), 4),
+            (error(result).unwrap(), requests)
+        );
+    }
+
+    #[test]
+    fn rate_limiter() {
+        let limiter = RateLimiter::new(temp_path("rate_limiter"), Duration::from_secs(60));
+        assert_eq!(Duration::ZERO, limiter.remaining());
+        limiter.record().unwrap();
+        let remaining = limiter.remaining();
+        assert!(remaining > Duration::from_secs(50) && remaining <= Duration::from_secs(60));
+
+        let limiter = RateLimiter::new(limiter.path, Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(Duration::ZERO, limiter.remaining());
+    }
+
+    #[test]
+    fn stub_server() {
+        let (base_url, server) = stub_server::serve(vec![
+            ("503 Service Unavailable", String::new()),
+            ("200 OK", "1,2,3\n".to_owned()),
+        ]);
+
+        let input = downloader(UreqClient::default(), &base_url, "stub_server")
+            .input(2015, 3, "secret")
+            .unwrap();
+        assert_eq!(b"1,2,3\n".to_vec(), input);
+        let requests = server.join().unwrap();
+        assert_eq!(2, requests.len());
+        let request = &requests[1].head;
+        assert!(request.starts_with("get /2015/day/3/input http/1.1\r\n"));
+        assert!(request.contains("cookie: session=secret\r\n"));
+        assert!(request.contains(&format!(
+            "user-agent: {}\r\n",
+            USER_AGENT.to_ascii_lowercase()
+        )));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    cli::Options,
+    day::{Day, Part},
+    inputs::Inputs,
+    output::{self, RecordWriter},
+    print_day_header,
+};
+use anyhow::{anyhow, Context};
+use colored::Colorize;
+use std::path::Path;
+
+/// A `<pre><code>` block from a puzzle description, a candidate example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// Part of the description the block appears in.
+    pub part: Part,
+    pub text: String,
+}
+
+/// Returns the contents between all non-overlapping `start` and `end` markers.
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some((_, after)) = rest.split_once(start) {
+        match after.split_once(end) {
+            Some((inner, after)) => {
+                found.push(inner);
+                rest = after;
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+/// Removes the tags from HTML, and decodes the entities.
+fn to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(i) = rest.find(['<', '&']) {
+        text.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with('<') {
+            rest = rest.split_once('>').map_or("", |(_, after)| after);
+            continue;
+        }
+        let entity = rest.find(';').map(|end| &rest[..=end]);
+        let decoded = match entity {
+            Some("&lt;") => '<',
+            Some("&gt;") => '>',
+            Some("&amp;") => '&',
+            Some("&quot;") => '"',
+            Some("&#39;" | "&apos;") => '\'',
+            _ => {
+                text.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        text.push(decoded);
+        rest = &rest[entity.unwrap().len()..];
+    }
+    text.push_str(rest);
+    text
+}
+
+fn articles(html: &str) -> impl Iterator<Item = (Part, &str)> {
+    let articles = between(html, "<article class=\"day-desc\">", "</article>");
+    [Part::One, Part::Two].into_iter().zip(articles)
+}
+
+/// Extracts the examples from a puzzle description, without duplicates.
+pub fn extract(html: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+    for (part, article) in articles(html) {
+        for block in between(article, "<pre><code>", "</code></pre>") {
+            let text = to_text(block).trim_end_matches('\n').to_owned();
+            if !text.is_empty() && examples.iter().all(|example| example.text != text) {
+                examples.push(Example { part, text });
+            }
+        }
+    }
+    examples
+}
+
+/// Guesses the answer of each part for the examples, which is usually the
+/// last highlighted code in the description of that part.
+pub fn answers(html: &str) -> [Option<String>; 2] {
+    let mut answers = [None, None];
+    for ((_, article), answer) in articles(html).zip(&mut answers) {
+        *answer = between(article, "<code><em>", "</em></code>")
+            .last()
+            .map(|answer| to_text(answer));
+    }
+    answers
+}
+
+/// Formats an example as a byte string literal, in the style of the tests.
+fn literal(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+    // A line continuation would also skip the leading whitespace.
+    if escaped.starts_with(char::is_whitespace) {
+        format!("b\"{escaped}\"")
+    } else {
+        format!("b\"\\\n{escaped}\"")
+    }
+}
+
+/// Finds the byte range of the body of the `tests!` block, skipping over
+/// string literals and comments while matching braces.
+fn tests_block(source: &str) -> Option<(usize, usize)> {
+    let start = source.find("tests! {")? + "tests! {".len();
+    let bytes = source.as_bytes();
+    let (mut i, mut depth) = (start, 1);
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((start, i));
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Adds the examples as constants to the `tests!` block of a day, creating
+/// it when needed, together with commented out tests for each part that use
+/// the guessed answers. Examples that are already present are skipped.
+/// Returns the new source, and the name of the constant of each example that
+/// was added.
+pub fn scaffold(
+    source: &str,
+    examples: &[&Example],
+    answers: &[Option<String>; 2],
+) -> (String, Vec<Option<String>>) {
+    let existing = between(source, "const EXAMPLE", ":")
+        .into_iter()
+        .filter_map(|suffix| match suffix {
+            "" => Some(1),
+            suffix => suffix.parse::<usize>().ok(),
+        })
+        .max();
+    let literals = examples
+        .iter()
+        .map(|example| literal(&example.text))
+        .collect::<Vec<_>>();
+    let is_new = literals
+        .iter()
+        .map(|literal| !source.contains(literal.as_str()))
+        .collect::<Vec<_>>();
+    let examples = examples
+        .iter()
+        .zip(&literals)
+        .zip(&is_new)
+        .filter(|(_, &is_new)| is_new)
+        .map(|(x, _)| x)
+        .collect::<Vec<_>>();
+    if examples.is_empty() {
+        return (source.to_owned(), vec![None; is_new.len()]);
+    }
+    let names: Vec<String> = match existing {
+        None if examples.len() == 1 => vec!["EXAMPLE".to_owned()],
+        _ => {
+            let first = existing.unwrap_or(0) + 1;
+            (first..first + examples.len())
+                .map(|nr| format!("EXAMPLE{nr}"))
+                .collect()
+        }
+    };
+
+    let mut block = String::new();
+    for (name, (_, literal)) in names.iter().zip(&examples) {
+        block.push_str(&format!("    const {name}: &'static [u8] = {literal};\n\n"));
+    }
+    for (part, pt, function, answer) in [
+        (Part::One, "pt1", "part1", &answers[0]),
+        (Part::Two, "pt2", "part2", &answers[1]),
+    ] {
+        let name = names
+            .iter()
+            .zip(&examples)
+            .find(|(_, (example, _))| example.part == part)
+            .or_else(|| names.iter().zip(&examples).next())
+            .map(|(name, _)| name);
+        let answer = answer.as_deref().unwrap_or("?");
+        if let Some(name) = name {
+            block.push_str(&format!(
+                "    // simple_tests!(parse, {function}, {pt}_tests, {name} => {answer});\n"
+            ));
+        }
+    }
+
+    let source = match tests_block(source) {
+        Some((start, end)) => {
+            let body = source[start..end].trim_end();
+            let separator = if body.is_empty() { "\n" } else { "\n\n" };
+            format!(
+                "{}{body}{separator}{block}{}",
+                &source[..start],
+                &source[end..]
+            )
+        }
+        None => format!("{}\n\ntests! {{\n{block}}}\n", source.trim_end()),
+    };
+    let mut names = names.into_iter();
+    let names = is_new
+        .into_iter()
+        .map(|is_new| if is_new { names.next() } else { None })
+        .collect();
+    (source, names)
+}
+
+/// Extracts the examples from the puzzle description of a day, and adds the
+/// selected ones to its tests when `--write` is given.
+pub(crate) fn examples_day(
+    inputs: &mut Inputs,
+    root: &Path,
+    options: &Options,
+    writer: &mut RecordWriter,
+    day: &dyn Day,
+) -> bool {
+    let day_nr = day.nr();
+    let source_path = root.join("src").join(format!("day{day_nr:0>2}.rs"));
+    let result = inputs.puzzle(day_nr).and_then(|html| {
+        let examples = extract(&html);
+        let answers = answers(&html);
+        let picked = if options.pick.is_empty() {
+            [Part::One, Part::Two]
+                .iter()
+                .filter_map(|&part| examples.iter().position(|e| e.part == part))
+                .collect::<Vec<_>>()
+        } else {
+            let picked = options.pick.iter().map(|nr| nr - 1).collect::<Vec<_>>();
+            if let Some(nr) = options.pick.iter().find(|&&nr| nr > examples.len()) {
+                return Err(anyhow!(
+                    "there is no example {nr}, there are {}",
+                    examples.len()
+                ));
+            }
+            picked
+        };
+        let mut constants = vec![None; examples.len()];
+        if options.write {
+            let source = std::fs::read_to_string(&source_path)
+                .with_context(|| format!("failed to read {}", source_path.display()))?;
+            let selected = picked.iter().map(|&i| &examples[i]).collect::<Vec<_>>();
+            let (source, names) = scaffold(&source, &selected, &answers);
+            std::fs::write(&source_path, source)
+                .with_context(|| format!("failed to write {}", source_path.display()))?;
+            for (&i, name) in picked.iter().zip(names) {
+                constants[i] = name;
+            }
+        }
+        Ok((examples, answers, picked, constants))
+    });
+    let (examples, answers, picked, constants) = match result {
+        Ok(x) => x,
+        Err(e) => {
+            let error = format!("{e:#}");
+            writer.write(&output::examples_failed_record(day_nr, &error));
+            if writer.is_text() {
+                print_day_header(day_nr, None);
+                println!(" :: {}", error.bright_red());
+            }
+            return false;
+        }
+    };
+    let answer = |example: &Example| match example.part {
+        Part::One => answers[0].as_deref(),
+        Part::Two => answers[1].as_deref(),
+    };
+    for (i, example) in examples.iter().enumerate() {
+        let constant = constants[i].as_deref();
+        let record = output::example_record(day_nr, i + 1, example, answer(example), constant);
+        writer.write(&record);
+    }
+    if !writer.is_text() {
+        return true;
+    }
+
+    print_day_header(day_nr, None);
+    print!(" :: {} examples", examples.len());
+    for (pt, answer) in ["pt1", "pt2"].iter().zip(&answers) {
+        if let Some(answer) = answer {
+            print!(" :: {} {}", pt.bright_green(), answer.bold());
+        }
+    }
+    println!();
+    const MAX_LINES: usize = 8;
+    for (i, example) in examples.iter().enumerate() {
+        let part = if example.part == Part::One {
+            "pt1"
+        } else {
+            "pt2"
+        };
+        print!(
+            "    {} {}",
+            format!("#{}", i + 1).bright_blue(),
+            part.bright_green()
+        );
+        match (&constants[i], picked.contains(&i) && options.write) {
+            (Some(name), _) => print!(" :: {} {name}", "added as".bright_green()),
+            (None, true) => print!(" :: {}", "already in the tests".bright_black()),
+            (None, false) => {}
+        }
+        println!();
+        let lines = example.text.lines().collect::<Vec<_>>();
+        for line in lines.iter().take(MAX_LINES) {
+            println!("        {line}");
+        }
+        if lines.len() > MAX_LINES {
+            let more = format!("({} more lines)", lines.len() - MAX_LINES);
+            println!("        {}", more.bright_black());
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 5: Test ---</h2><p>For example:</p>
+<pre><code>0,9 -&gt; 5,9
+8,0 -&gt; 0,8
+</code></pre>
+<p>A line: <code>1,1 -&gt; 1,3</code>.</p>
+<pre><code>.......1..
+..<em>2</em>....
+</code></pre>
+<p>In total, <code><em>5</em></code> points.</p>
+</article>
+<p>Your puzzle answer was <code>6007</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>0,9 -&gt; 5,9
+8,0 -&gt; 0,8
+</code></pre>
+<p>You now get <code><em>12</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn extraction() {
+        let examples = extract(PAGE);
+        assert_eq!(
+            vec![
+                Example {
+                    part: Part::One,
+                    text: "0,9 -> 5,9\n8,0 -> 0,8".to_owned()
+                },
+                Example {
+                    part: Part::One,
+                    text: ".......1..\n..2....".to_owned()
+                },
+            ],
+            examples
+        );
+        assert_eq!([Some("5".to_owned()), Some("12".to_owned())], answers(PAGE));
+        assert_eq!(
+            "a < b &x; \"c\"",
+            to_text("<b>a &lt; b</b> &x; &quot;c&quot;")
+        );
+        assert_eq!("b\"  x\\\\\"", literal("  x\\"));
+    }
+
+    #[test]
+    fn scaffolding() {
+        let examples = extract(PAGE);
+        let answers = answers(PAGE);
+        let source = "fn part1() {}\n";
+        let (source, names) = scaffold(source, &[&examples[0]], &answers);
+        assert_eq!(vec![Some("EXAMPLE".to_owned())], names);
+        assert_eq!(
+            "fn part1() {}\n\ntests! {\n    const EXAMPLE: &'static [u8] = b\"\\\n0,9 -> 5,9\n8,0 -> 0,8\";\n\n    \
+             // simple_tests!(parse, part1, pt1_tests, EXAMPLE => 5);\n    \
+             // simple_tests!(parse, part2, pt2_tests, EXAMPLE => 12);\n}\n",
+            source
+        );
+
+        let (unchanged, names) = scaffold(&source, &[&examples[0]], &answers);
+        assert_eq!((source.as_str(), vec![None]), (unchanged.as_str(), names));
+
+        let existing = "tests! {\n    const EXAMPLE: &'static [u8] = b\"{\";\n    \
+                        simple_tests!(parse, part1, pt1_tests, EXAMPLE => 1);\n}\n\nfn after() {}\n";
+        let (source, names) = scaffold(existing, &[&examples[1]], &[None, None]);
+        assert_eq!(vec![Some("EXAMPLE2".to_owned())], names);
+        assert!(source.starts_with(
+            "tests! {\n    const EXAMPLE: &'static [u8] = b\"{\";\n    \
+             simple_tests!(parse, part1, pt1_tests, EXAMPLE => 1);\n\n    \
+             const EXAMPLE2: &'static [u8] = b\"\\\n.......1..\n..2....\";\n"
+        ));
+        assert!(source.ends_with(
+            "// simple_tests!(parse, part2, pt2_tests, EXAMPLE2 => ?);\n}\n\nfn after() {}\n"
+        ));
+    }
+}
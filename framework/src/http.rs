@@ -0,0 +1,144 @@
+use anyhow::Result;
+use std::{io::Read, time::Duration};
+
+pub const USER_AGENT: &str = concat!(
+    "advent_of_code_2021/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/Aidiakapi/advent_of_code_2021)"
+);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+/// Minimal HTTP client, so that downloads can be tested without reaching out
+/// to the Advent of Code website.
+pub trait HttpClient: Send {
+    /// Performs a GET request. Responses with an error status are returned as
+    /// a response, only transport failures are errors.
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response>;
+    /// Performs a POST request, with the same error handling as `get`.
+    fn post(&self, url: &str, headers: &[(&str, &str)], body: &[u8]) -> Result<Response>;
+}
+
+#[derive(Debug)]
+pub struct UreqClient {
+    pub timeout: Duration,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        UreqClient {
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl UreqClient {
+    fn request(&self, method: &str, url: &str, headers: &[(&str, &str)]) -> ureq::Request {
+        let mut request = ureq::request(method, url).timeout(self.timeout);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        request
+    }
+}
+
+fn into_response(result: Result<ureq::Response, ureq::Error>) -> Result<Response> {
+    let response = match result {
+        Ok(response) => response,
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return Err(e.into()),
+    };
+    let status = response.status();
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body)?;
+    Ok(Response { status, body })
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response> {
+        into_response(self.request("GET", url, headers).call())
+    }
+
+    fn post(&self, url: &str, headers: &[(&str, &str)], body: &[u8]) -> Result<Response> {
+        into_response(self.request("POST", url, headers).send_bytes(body))
+    }
+}
+
+/// Percent-encodes a value for use in a query string or form body.
+pub fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// A local HTTP server for tests, which replies to each request with the next
+/// of the given responses, and returns the requests it received.
+#[cfg(test)]
+pub(crate) mod stub_server {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// A received request, with the headers in lowercase.
+    pub struct Request {
+        pub head: String,
+        pub body: String,
+    }
+
+    /// Starts the server, returning its base URL.
+    pub fn serve(responses: Vec<(&'static str, String)>) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(&mut stream);
+                let mut head = String::new();
+                while !head.ends_with("\r\n\r\n") && reader.read_line(&mut head).unwrap() > 0 {}
+                let head = head.to_ascii_lowercase();
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .map_or(0, |length| length.trim().parse().unwrap());
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+                requests.push(Request {
+                    head,
+                    body: String::from_utf8(request_body).unwrap(),
+                });
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (base_url, server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding() {
+        assert_eq!("abc-123_x.y~", url_encode("abc-123_x.y~"));
+        assert_eq!("a%20b%26c%3D%2B%C3%A9", url_encode("a b&c=+é"));
+    }
+}
@@ -1,64 +1,646 @@
-use anyhow::Result;
+use crate::{
+    baseline,
+    cli::{InputSource, Options},
+    config::Config,
+    crypto::Key,
+    day::Day,
+    download::{self, Downloader, RateLimiter},
+    http::UreqClient,
+    output::{self, RecordWriter},
+    print_day_header,
+};
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
 use std::{
+    fmt::Write,
     io::Read,
-    path::Path,
-    time::{Duration, Instant},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-const MINIMUM_TIME_BETWEEN_DOWNLOADS: Duration = Duration::from_secs(3);
+/// Metadata of a downloaded input, stored next to it as `XX.meta`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// Unix timestamp of the download.
+    pub downloaded: u64,
+    pub checksum: String,
+    pub source: String,
+}
+
+impl Metadata {
+    fn new(input: &[u8], source: String) -> Metadata {
+        let downloaded = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+        Metadata {
+            downloaded,
+            checksum: checksum(input),
+            source,
+        }
+    }
+
+    fn parse(contents: &str) -> Option<Metadata> {
+        let field = |key: &str| {
+            let prefix = format!("{key}: ");
+            let line = contents.lines().find(|line| line.starts_with(&prefix))?;
+            Some(line[prefix.len()..].to_owned())
+        };
+        Some(Metadata {
+            downloaded: field("downloaded")?.parse().ok()?,
+            checksum: field("checksum")?,
+            source: field("source")?,
+        })
+    }
+
+    fn serialize(&self) -> String {
+        format!(
+            "downloaded: {}\nchecksum: {}\nsource: {}\n",
+            self.downloaded, self.checksum, self.source
+        )
+    }
+}
+
+/// 64-bit FNV-1a hash, which is plenty to detect accidental changes.
+fn checksum(input: &[u8]) -> String {
+    let hash = input.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    let mut str = "fnv1a64:".to_owned();
+    write!(str, "{hash:016x}").unwrap();
+    str
+}
+
+/// Returns why the contents are probably not a puzzle input, but an error
+/// page, if they look like one.
+pub fn suspicious(input: &[u8]) -> Option<&'static str> {
+    let text = String::from_utf8_lossy(input);
+    let text = text.trim_start();
+    let start = text.get(..15).unwrap_or(text).to_ascii_lowercase();
+    if text.trim().is_empty() {
+        Some("is empty")
+    } else if start.starts_with("<!doctype html") || start.starts_with("<html") {
+        Some("is a web page")
+    } else if text.starts_with("Puzzle inputs differ by user") || text.starts_with("Please log in")
+    {
+        Some("is a login error, the session key has probably expired")
+    } else if text.starts_with("Please don't repeatedly request this endpoint") {
+        Some("is an error, the puzzle was not unlocked yet")
+    } else if text.starts_with("500 Internal Server Error") {
+        Some("is a server error")
+    } else {
+        None
+    }
+}
+
+/// State of a cached input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    Missing,
+    /// Cached without metadata, such as an input that was placed by hand.
+    Unverified,
+    Ok(Metadata),
+    /// Changed since it was downloaded.
+    Modified(Metadata),
+    Broken(&'static str),
+    /// Could not be read, such as an encrypted input without the right key.
+    Unreadable(String),
+}
+
+impl CacheStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CacheStatus::Missing => "missing",
+            CacheStatus::Unverified => "unverified",
+            CacheStatus::Ok(_) => "ok",
+            CacheStatus::Modified(_) => "modified",
+            CacheStatus::Broken(_) => "broken",
+            CacheStatus::Unreadable(_) => "unreadable",
+        }
+    }
+
+    pub fn metadata(&self) -> Option<&Metadata> {
+        match self {
+            CacheStatus::Ok(metadata) | CacheStatus::Modified(metadata) => Some(metadata),
+            _ => None,
+        }
+    }
+}
+
+fn encrypted_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".enc");
+    path.into()
+}
 
-#[derive(Debug, Default)]
+fn exists(path: &Path) -> bool {
+    path.is_file() || encrypted_path(path).is_file()
+}
+
+/// Cached inputs. When an input key is configured, inputs are stored
+/// encrypted, as `XX.txt.enc`, and are decrypted when they are read.
 pub struct Inputs {
+    config: Config,
     session_key: Option<String>,
-    last_download_time: Option<Instant>,
+    key: Option<Key>,
+    downloader: Downloader,
 }
 
 impl Inputs {
-    pub fn new() -> Inputs {
-        Default::default()
+    pub fn new(config: Config) -> Inputs {
+        let rate_limiter = RateLimiter::new(
+            config.input_dir.value.join(".last_download"),
+            download::MINIMUM_TIME_BETWEEN_DOWNLOADS,
+        );
+        let downloader = Downloader::new(
+            Box::<UreqClient>::default(),
+            download::BASE_URL,
+            rate_limiter,
+        );
+        Inputs {
+            config,
+            session_key: None,
+            key: None,
+            downloader,
+        }
     }
 
-    pub fn get(&mut self, day: u32) -> Result<Vec<u8>> {
-        let path = format!("./inputs/{day:0>2}.txt");
-        let path = Path::new(&path);
-        if let Ok(mut input) = std::fs::read(&path) {
-            input.retain(|c| *c != b'\r');
-            return Ok(input);
+    fn dir(&self) -> &Path {
+        &self.config.input_dir.value
+    }
+
+    /// Path of the input of a day, or of one of its named variants, which are
+    /// stored as either `inputs/XX/NAME.txt` or `inputs/XX.NAME.txt`.
+    pub fn path(&self, day: u32, variant: Option<&str>) -> PathBuf {
+        let name = match variant {
+            Some(name) => name,
+            None => return self.dir().join(format!("{day:0>2}.txt")),
+        };
+        let nested = self.dir().join(format!("{day:0>2}/{name}.txt"));
+        if exists(&nested) {
+            nested
+        } else {
+            self.dir().join(format!("{day:0>2}.{name}.txt"))
         }
+    }
+
+    /// Names of all named variants of the input of a day, sorted by name.
+    pub fn variants(&self, day: u32) -> Vec<String> {
+        let file_names = |dir: &Path| -> Vec<String> {
+            let entries = match std::fs::read_dir(dir) {
+                Ok(x) => x,
+                Err(_) => return Vec::new(),
+            };
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        };
+
+        let prefix = format!("{day:0>2}.");
+        let nested = file_names(&self.dir().join(format!("{day:0>2}")));
+        let nested = nested.iter().map(String::as_str);
+        let flat = file_names(self.dir());
+        let flat = flat.iter().filter_map(|name| name.strip_prefix(&prefix));
+        let mut variants = nested
+            .chain(flat)
+            .filter_map(|name| {
+                let name = name.strip_suffix(".enc").unwrap_or(name);
+                name.strip_suffix(".txt")
+            })
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        variants.sort_unstable();
+        variants.dedup();
+        variants
+    }
+
+    /// Path of the file that holds the input, which is the encrypted file when
+    /// there is no plaintext one.
+    pub fn stored_path(&self, day: u32, variant: Option<&str>) -> PathBuf {
+        let path = self.path(day, variant);
+        let encrypted = encrypted_path(&path);
+        if !path.is_file() && encrypted.is_file() {
+            encrypted
+        } else {
+            path
+        }
+    }
 
-        let input = self.download(day)?;
+    pub fn is_cached(&self, day: u32) -> bool {
+        exists(&self.path(day, None))
+    }
+
+    fn key(&mut self) -> Result<&Key> {
+        if self.key.is_none() {
+            self.key = Some(Key::derive(&self.config.input_key()?));
+        }
+        Ok(self.key.as_ref().unwrap())
+    }
+
+    /// Reads an input, decrypting it when there is only an encrypted file.
+    /// Returns `None` when there is neither.
+    fn read(&mut self, path: &Path) -> Result<Option<Vec<u8>>> {
+        if let Ok(input) = std::fs::read(path) {
+            return Ok(Some(input));
+        }
+        let encrypted_path = encrypted_path(path);
+        let data = match std::fs::read(&encrypted_path) {
+            Ok(x) => x,
+            Err(_) => return Ok(None),
+        };
+        let display = encrypted_path.display();
+        let key = self
+            .key()
+            .map_err(|e| anyhow!("{display} is encrypted, but {e:#}"))?;
+        let input = key
+            .decrypt(&data)
+            .map_err(|e| anyhow!("failed to decrypt {display}: {e}"))?;
+        Ok(Some(input))
+    }
+
+    /// Stores an input, encrypted when an input key is configured. Fails when
+    /// the configured key can't be read, rather than storing it in plain text.
+    fn write(&mut self, path: &Path, input: &[u8]) -> Result<()> {
         std::fs::create_dir_all(path.parent().unwrap())?;
-        std::fs::write(&path, &input)?;
+        let (path, data) = if self.config.has_input_key() {
+            (encrypted_path(path), self.key()?.encrypt(input)?)
+        } else {
+            (path.to_owned(), input.to_vec())
+        };
+        std::fs::write(&path, data).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Encrypts the plaintext inputs of a day and its variants, and the cached
+    /// puzzle description, and removes the plaintext files. Returns the paths
+    /// of the encrypted files.
+    pub fn encrypt(&mut self, day: u32) -> Result<Vec<PathBuf>> {
+        self.key()?;
+        let mut encrypted = Vec::new();
+        let variants = std::iter::once(None).chain(self.variants(day).into_iter().map(Some));
+        let mut paths = variants
+            .map(|variant| self.path(day, variant.as_deref()))
+            .collect::<Vec<_>>();
+        paths.push(self.puzzle_path(day));
+        for path in paths {
+            let input = match std::fs::read(&path) {
+                Ok(x) => x,
+                Err(_) => continue,
+            };
+            self.write(&path, &input)?;
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+            encrypted.push(encrypted_path(&path));
+        }
+        Ok(encrypted)
+    }
+
+    fn metadata_path(&self, day: u32) -> PathBuf {
+        self.dir().join(format!("{day:0>2}.meta"))
+    }
+
+    fn quarantine_path(&self, day: u32) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_millis());
+        self.dir()
+            .join("quarantine")
+            .join(format!("{day:0>2}-{timestamp}.txt"))
+    }
+
+    /// Checks the cached input of a day against its metadata.
+    pub fn status(&mut self, day: u32) -> CacheStatus {
+        let input = match self.read(&self.path(day, None)) {
+            Ok(Some(x)) => x,
+            Ok(None) => return CacheStatus::Missing,
+            Err(e) => return CacheStatus::Unreadable(format!("{e:#}")),
+        };
+        if let Some(reason) = suspicious(&input) {
+            return CacheStatus::Broken(reason);
+        }
+        let metadata = std::fs::read_to_string(self.metadata_path(day))
+            .ok()
+            .and_then(|contents| Metadata::parse(&contents));
+        match metadata {
+            None => CacheStatus::Unverified,
+            Some(metadata) if metadata.checksum == checksum(&input) => CacheStatus::Ok(metadata),
+            Some(metadata) => CacheStatus::Modified(metadata),
+        }
+    }
+
+    /// Moves the cached input of a day to the quarantine directory, and
+    /// downloads it again.
+    pub fn refetch(&mut self, day: u32) -> Result<Vec<u8>> {
+        let path = self.stored_path(day, None);
+        if path.is_file() {
+            let quarantine = self.quarantine_path(day);
+            std::fs::create_dir_all(quarantine.parent().unwrap())?;
+            std::fs::rename(&path, &quarantine).with_context(|| {
+                format!(
+                    "failed to move {} to {}",
+                    path.display(),
+                    quarantine.display()
+                )
+            })?;
+            let _ = std::fs::remove_file(self.metadata_path(day));
+        }
+        self.get(day, None)
+    }
+
+    /// Returns the input as stored, it still has to be normalized.
+    pub fn get(&mut self, day: u32, variant: Option<&str>) -> Result<Vec<u8>> {
+        let path = self.path(day, variant);
+        let source = self.config.input_dir.source.clone();
+        if let Some(name) = variant {
+            let input = self.read(&path)?.ok_or_else(|| {
+                anyhow!(
+                    "input {name} not found at {} (input directory from {source})",
+                    path.display()
+                )
+            })?;
+            return Ok(input);
+        }
+        if let Some(input) = self.read(&path)? {
+            if let Some(reason) = suspicious(&input) {
+                return Err(anyhow!(
+                    "the cached input at {} {reason}, use `inputs verify --refetch` to download it again",
+                    path.display()
+                ));
+            }
+            return Ok(input);
+        }
+
+        let not_found = format!(
+            "input not found at {} (input directory from {source}), and downloading it failed",
+            path.display()
+        );
+        let input = self
+            .download(day)
+            .map_err(|e| anyhow!("{not_found}: {e:#}"))?;
+        if let Some(reason) = suspicious(&input) {
+            let quarantine = self.quarantine_path(day);
+            std::fs::create_dir_all(quarantine.parent().unwrap())?;
+            std::fs::write(&quarantine, &input)?;
+            return Err(anyhow!(
+                "the downloaded input {reason}, it was stored at {} instead",
+                quarantine.display()
+            ));
+        }
+        self.write(&path, &input)?;
+        let metadata = Metadata::new(&input, self.downloader.input_url(self.config.year, day));
+        std::fs::write(self.metadata_path(day), metadata.serialize())?;
         Ok(input)
     }
 
-    fn get_session_key(&mut self) -> Result<&str> {
+    /// Path of the cached description of a puzzle.
+    pub fn puzzle_path(&self, day: u32) -> PathBuf {
+        self.dir().join("puzzles").join(format!("{day:0>2}.html"))
+    }
+
+    /// Returns the description of a puzzle, from the cache when it is there,
+    /// so that it works offline, and downloads it otherwise. Like the inputs,
+    /// it is stored encrypted when an input key is configured.
+    pub fn puzzle(&mut self, day: u32) -> Result<String> {
+        let path = self.puzzle_path(day);
+        if let Some(html) = self.read(&path)? {
+            return Ok(String::from_utf8_lossy(&html).into_owned());
+        }
+        let year = self.config.year;
+        let html = self
+            .session_key()
+            .map(str::to_owned)
+            .and_then(|session_key| self.downloader.puzzle(year, day, &session_key))
+            .with_context(|| {
+                format!(
+                    "puzzle not found at {}, and downloading it failed",
+                    path.display()
+                )
+            })?;
+        let html = String::from_utf8_lossy(&html).into_owned();
+        if !html.contains("<article") {
+            return Err(anyhow!("the downloaded page is not a puzzle description"));
+        }
+        self.write(&path, html.as_bytes())?;
+        Ok(html)
+    }
+
+    pub fn load(&mut self, source: &InputSource) -> Result<Vec<u8>> {
+        let input = match source {
+            InputSource::File(path) => std::fs::read(path)
+                .with_context(|| format!("failed to read {}", path.display()))?,
+            InputSource::Stdin => {
+                let mut buf = Vec::new();
+                std::io::stdin()
+                    .read_to_end(&mut buf)
+                    .context("failed to read stdin")?;
+                buf
+            }
+        };
+        Ok(input)
+    }
+
+    pub fn session_key(&mut self) -> Result<&str> {
         if self.session_key.is_none() {
-            self.session_key = Some(std::fs::read_to_string("./session_key.txt")?);
+            self.session_key = Some(self.config.session_key()?);
         }
         Ok(self.session_key.as_ref().unwrap())
     }
 
     fn download(&mut self, day: u32) -> Result<Vec<u8>> {
-        let session_key = self.get_session_key()?;
-        let cookie_values = format!("session={session_key}");
-
-        let current_time = Instant::now();
-        if let Some(last_time) = self.last_download_time {
-            let delta_time = current_time - last_time;
-            if delta_time < MINIMUM_TIME_BETWEEN_DOWNLOADS {
-                std::thread::sleep(delta_time - MINIMUM_TIME_BETWEEN_DOWNLOADS);
+        let session_key = self.session_key()?.to_owned();
+        self.downloader.input(self.config.year, day, &session_key)
+    }
+}
+
+pub(crate) fn verify_day(
+    inputs: &mut Inputs,
+    options: &Options,
+    writer: &mut RecordWriter,
+    day: &dyn Day,
+) -> bool {
+    let day_nr = day.nr();
+    let status = inputs.status(day_nr);
+    let refetch = match (&status, options.refetch) {
+        (CacheStatus::Broken(_), true) => Some(
+            inputs
+                .refetch(day_nr)
+                .map(|_| ())
+                .map_err(|e| format!("{e:#}")),
+        ),
+        _ => None,
+    };
+    let is_broken = matches!(status, CacheStatus::Broken(_) | CacheStatus::Unreadable(_));
+    let success = !is_broken || matches!(refetch, Some(Ok(())));
+    writer.write(&output::verify_record(day_nr, &status, refetch.as_ref()));
+    if !writer.is_text() {
+        return success;
+    }
+
+    print_day_header(day_nr, None);
+    let name = match status {
+        CacheStatus::Ok(_) => status.name().bright_green(),
+        CacheStatus::Broken(_) | CacheStatus::Unreadable(_) => status.name().bright_red(),
+        _ => status.name().bright_yellow(),
+    };
+    print!(" :: {name}");
+    match &status {
+        CacheStatus::Missing => {}
+        CacheStatus::Unverified => print!(" :: no metadata"),
+        CacheStatus::Ok(metadata) | CacheStatus::Modified(metadata) => print!(
+            " :: downloaded {}",
+            baseline::format_timestamp(metadata.downloaded)
+        ),
+        CacheStatus::Broken(reason) => print!(" :: input {reason}"),
+        CacheStatus::Unreadable(reason) => print!(" :: {reason}"),
+    }
+    match refetch {
+        Some(Ok(())) => print!(" :: {}", "refetched".bright_green()),
+        Some(Err(e)) => print!(" :: {}", format!("refetch failed: {e}").bright_red()),
+        None => {}
+    }
+    println!();
+    success
+}
+
+pub(crate) fn encrypt_day(inputs: &mut Inputs, writer: &mut RecordWriter, day: &dyn Day) -> bool {
+    let day_nr = day.nr();
+    let result = inputs.encrypt(day_nr).map_err(|e| format!("{e:#}"));
+    writer.write(&output::encrypt_record(day_nr, &result));
+    if writer.is_text() {
+        print_day_header(day_nr, None);
+        match &result {
+            Ok(paths) if paths.is_empty() => {
+                println!(" :: {}", "no plaintext inputs".bright_black())
+            }
+            Ok(paths) => {
+                let names = paths
+                    .iter()
+                    .filter_map(|path| path.file_name())
+                    .map(|name| name.to_string_lossy())
+                    .collect::<Vec<_>>();
+                println!(" :: {} {}", "encrypted".bright_green(), names.join(", "));
             }
+            Err(e) => println!(" :: {}", e.bright_red()),
         }
-        self.last_download_time = Some(current_time);
+    }
+    result.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Secret, Setting};
+
+    fn temp_inputs(name: &str, key: Option<&str>) -> (Inputs, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = match key {
+            Some(key) => Secret::Value(key.to_owned()),
+            None => Secret::File(dir.join("input_key.txt")),
+        };
+        let inputs = Inputs::new(Config {
+            year: 2021,
+            input_dir: Setting {
+                value: dir.clone(),
+                source: "test".to_owned(),
+            },
+            session: Vec::new(),
+            input_key: vec![Setting {
+                value: key,
+                source: "test".to_owned(),
+            }],
+        });
+        (inputs, dir)
+    }
+
+    #[test]
+    fn suspicious_inputs() {
+        assert_eq!(None, suspicious(b"1\n2\n3\n"));
+        assert_eq!(None, suspicious(b"not a <html> page"));
+        assert_eq!(Some("is empty"), suspicious(b" \n"));
+        assert_eq!(Some("is a web page"), suspicious(b"\n<!DOCTYPE html>\n"));
+        assert!(suspicious(b"Puzzle inputs differ by user.  Please log in").is_some());
+    }
+
+    #[test]
+    fn cache_status() {
+        let (mut inputs, dir) = temp_inputs("cache", None);
+
+        assert_eq!(CacheStatus::Missing, inputs.status(1));
+        std::fs::write(dir.join("01.txt"), "1\n2\n").unwrap();
+        assert_eq!(CacheStatus::Unverified, inputs.status(1));
+        let metadata = Metadata::new(b"1\n2\n", "https://example.com/1".to_owned());
+        assert_eq!(
+            Some(&metadata),
+            Metadata::parse(&metadata.serialize()).as_ref()
+        );
+        std::fs::write(dir.join("01.meta"), metadata.serialize()).unwrap();
+        assert_eq!(CacheStatus::Ok(metadata.clone()), inputs.status(1));
+        std::fs::write(dir.join("01.txt"), "1\n3\n").unwrap();
+        assert_eq!(CacheStatus::Modified(metadata), inputs.status(1));
+        std::fs::write(dir.join("01.txt"), "<html></html>").unwrap();
+        assert_eq!(CacheStatus::Broken("is a web page"), inputs.status(1));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn encryption() {
+        let (mut inputs, dir) = temp_inputs("encrypted", None);
+        std::fs::write(dir.join("01.txt"), "1\n2\n").unwrap();
+        let error = inputs.encrypt(1).unwrap_err();
+        assert!(error.to_string().contains("no input key found"));
+        inputs.write(&dir.join("02.txt"), b"3\n").unwrap();
+        assert!(dir.join("02.txt").exists());
+        std::fs::write(dir.join("04.txt.enc"), "encrypted").unwrap();
+        let error = inputs.get(4, None).unwrap_err();
+        assert!(format!("{error:#}").contains("is encrypted, but no input key found"));
+        assert_eq!("unreadable", inputs.status(4).name());
+
+        let mut misconfigured = Inputs::new(Config {
+            input_key: vec![
+                Setting {
+                    value: Secret::File(dir.join("typo.txt")),
+                    source: "AOC_INPUT_KEY_FILE".to_owned(),
+                },
+                Setting {
+                    value: Secret::File(dir.join("input_key.txt")),
+                    source: "crate root".to_owned(),
+                },
+            ],
+            ..inputs.config.clone()
+        });
+        assert!(misconfigured.write(&dir.join("03.txt"), b"4\n").is_err());
+        assert!(!dir.join("03.txt").exists());
 
-        let resp = ureq::get(&format!("https://adventofcode.com/2021/day/{day}/input"))
-            .set("cookie", &cookie_values)
-            .timeout(Duration::from_secs(5))
-            .call()?;
+        let (mut inputs, dir) = temp_inputs("encrypted", Some("hunter2"));
+        std::fs::write(dir.join("01.txt"), "1\n2\n").unwrap();
+        std::fs::create_dir_all(dir.join("01")).unwrap();
+        std::fs::write(dir.join("01").join("alt.txt"), "3\n").unwrap();
+        let mut encrypted = inputs.encrypt(1).unwrap();
+        encrypted.sort();
+        assert_eq!(
+            vec![dir.join("01").join("alt.txt.enc"), dir.join("01.txt.enc")],
+            encrypted
+        );
+        assert!(!dir.join("01.txt").exists());
+        assert_eq!(vec!["alt".to_owned()], inputs.variants(1));
+        assert_eq!(b"1\n2\n".to_vec(), inputs.get(1, None).unwrap());
+        assert_eq!(b"3\n".to_vec(), inputs.get(1, Some("alt")).unwrap());
+        assert_eq!(CacheStatus::Unverified, inputs.status(1));
 
-        let mut buf = Vec::new();
-        resp.into_reader().read_to_end(&mut buf)?;
-        Ok(buf)
+        let config = inputs.config.clone();
+        let mut wrong_key = Inputs::new(Config {
+            input_key: vec![Setting {
+                value: Secret::Value("hunter3".to_owned()),
+                source: "test".to_owned(),
+            }],
+            ..config
+        });
+        let error = wrong_key.get(1, None).unwrap_err();
+        assert!(format!("{error:#}").contains("failed to decrypt"));
+        assert_eq!("unreadable", wrong_key.status(1).name());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -1,5 +1,6 @@
 #![allow(incomplete_features)]
 #![feature(
+    alloc_error_hook,
     allocator_api,
     associated_type_bounds,
     auto_traits,
@@ -12,162 +13,834 @@
     trait_alias
 )]
 
+pub mod allocator;
+mod answers;
 pub mod array;
 pub mod astar;
+pub mod baseline;
+pub mod bench;
+pub mod cli;
+pub mod config;
+pub mod crypto;
 pub mod day;
-mod inputs;
+pub mod download;
+pub mod examples;
+pub mod http;
+pub mod inputs;
+pub mod normalize;
+pub mod output;
+mod parallel;
+pub mod params;
 pub mod parsers;
 pub mod prelude;
+pub mod render;
+pub mod rng;
+pub mod sandbox;
 pub mod submissions;
+pub mod submit;
 pub mod utils;
 pub mod vec;
+mod watch;
 
-use crate::day::{BenchOutputs, ColoredOutput};
-use ahash::AHashSet;
+use allocator::{format_bytes, AllocStats};
+use answers::{Answers, Verification};
 use anyhow::Result;
+use baseline::{Baseline, Delta, Environment, History, Tracker};
+use bench::{BenchResult, Stats};
+use cli::{Cli, Command, InputSource, Options};
 use colored::{ColoredString, Colorize};
-use day::{Day, DayResult};
+use config::Config;
+use day::{Answer, Day, DayResult};
 use inputs::Inputs;
-use std::time::{Duration, Instant};
+use normalize::{Change, Normalization};
+use output::RecordWriter;
+use sandbox::Limits;
+use std::{
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+use submit::{SubmissionLog, Submitter};
+use watch::RunSummary;
 
+/// Declares the days of a library crate holding one year as public modules,
+/// for example `days!(day01, day02,)`, along with a `days` function returning
+/// all of them. Without arguments, the days are the `src/dayXX.rs` files found
+/// by the build script of the crate, see `build.rs` in the root of this
+/// repository.
 #[macro_export]
-macro_rules! main {
+macro_rules! days {
+    () => {
+        include!(concat!(env!("OUT_DIR"), "/days.rs"));
+        found_days!();
+    };
     ($($day:tt,)*) => {
+        $(pub mod $day;)*
+
+        /// All days, in order. They're built once and leaked, as
+        /// [`framework::run`] needs them to be `'static`.
+        pub fn days() -> &'static [&'static dyn $crate::day::Day] {
+            static DAYS: ::std::sync::OnceLock<::std::vec::Vec<&'static dyn $crate::day::Day>> =
+                ::std::sync::OnceLock::new();
+            DAYS.get_or_init(|| ::std::vec![
+                $(::std::boxed::Box::leak(::std::boxed::Box::new($day::day())) as &'static dyn $crate::day::Day,)*
+            ])
+        }
+    };
+}
+
+/// Defines the `main` function of a crate holding the days of one year, for
+/// example `main!(year = 2021, day01, day02,)`. With `lib`, the days are those
+/// of a library crate declared with [`days!`], e.g.
+/// `main!(year = 2021, lib = advent_of_code_2021)`.
+#[macro_export]
+macro_rules! main {
+    (year = $year:literal, lib = $lib:ident) => {
+        #[global_allocator]
+        static ALLOCATOR: framework::allocator::TrackingAllocator =
+            framework::allocator::TrackingAllocator;
+
+        pub fn main() -> ::std::process::ExitCode {
+            framework::run($year, env!("CARGO_MANIFEST_DIR"), $lib::days())
+        }
+    };
+    (year = $year:literal, $($day:tt,)*) => {
         $(mod $day;)*
 
-        pub fn main() {
-            framework::run(&[
-                $(&$day::day(),)*
+        #[global_allocator]
+        static ALLOCATOR: framework::allocator::TrackingAllocator =
+            framework::allocator::TrackingAllocator;
+
+        pub fn main() -> ::std::process::ExitCode {
+            framework::run($year, env!("CARGO_MANIFEST_DIR"), &[
+                $(::std::boxed::Box::leak(::std::boxed::Box::new($day::day())),)*
             ])
         }
     };
 }
 
-pub fn run(days: &[&dyn Day]) {
-    println!(
-        "\n🎄 {} {} {} {} 🎄\n",
-        "Advent".bright_red().bold(),
-        "of".bright_green(),
-        "Code".blue().bold(),
-        "2021".white().bold()
-    );
+/// Runs the command line interface. Days need to be `'static`, because a day
+/// that times out is left running in the background. Inputs are looked up
+/// relative to `root` unless configured otherwise, see [`Config`].
+pub fn run(year: u32, root: impl AsRef<Path>, days: &[&'static dyn Day]) -> ExitCode {
+    let mut args = std::env::args();
+    let program = args.next().unwrap_or_else(|| format!("aoc{year}"));
+    let options = match cli::parse_args(args) {
+        Ok(Cli::Options(options)) => *options,
+        Ok(Cli::Help) => {
+            println!("{}", cli::usage(&program));
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{} {e}\n", "error:".bright_red().bold());
+            eprintln!("{}", cli::usage(&program));
+            return ExitCode::from(2);
+        }
+    };
 
-    let args = std::env::args().collect::<Vec<String>>();
-    let is_bench = args.iter().any(|x| x == "--bench");
-    let specific_days = args
-        .iter()
-        .filter_map(|x| x.parse::<u32>().ok())
-        .collect::<AHashSet<u32>>();
+    let config = match Config::new(root.as_ref(), year, &options) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{} {e:#}", "error:".bright_red().bold());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let is_text = options.format.is_text();
+    if is_text {
+        println!(
+            "\n🎄 {} {} {} {} 🎄\n",
+            "Advent".bright_red().bold(),
+            "of".bright_green(),
+            "Code".blue().bold(),
+            year.to_string().white().bold()
+        );
+    }
 
-    let mut inputs = Inputs::new();
-    for &day in days {
-        if !specific_days.is_empty() && !specific_days.contains(&day.nr()) {
-            continue;
+    let mut writer = RecordWriter::new(options.format);
+    let mut success = true;
+    for &day_nr in &options.days {
+        if !days.iter().any(|day| day.nr() == day_nr) {
+            if is_text {
+                print_day_header(day_nr, None);
+                println!(" :: {}", "not implemented".bright_red());
+            } else {
+                writer.write(&output::not_implemented_record(day_nr, &options));
+            }
+            success = false;
         }
-        if is_bench {
-            bench_day(&mut inputs, day);
-        } else {
-            exec_day(&mut inputs, day);
+    }
+
+    let mut tracker = None;
+    let mut history = Vec::new();
+    let loaded = match options.command {
+        Command::Bench => {
+            Tracker::new(root.as_ref(), year, &options.baseline).map(|x| tracker = Some(x))
         }
+        Command::History => History::load(&baseline::dir(root.as_ref(), year)).map(|x| history = x),
+        Command::Run
+        | Command::List
+        | Command::VerifyInputs
+        | Command::EncryptInputs
+        | Command::Examples
+        | Command::Submit => Ok(()),
+    };
+    if let Err(e) = loaded {
+        eprintln!("{} {e:#}", "error:".bright_red().bold());
+        return ExitCode::FAILURE;
+    }
+    if let (true, Some(baseline)) = (is_text, tracker.as_ref().and_then(Tracker::baseline)) {
+        print_baseline_header(baseline);
     }
-    println!();
-}
 
-fn bench_day(inputs: &mut Inputs, day: &dyn Day) {
-    const MAX_ITER: u32 = 10_000;
-    const MAX_TIME: Duration = Duration::from_secs(5);
+    let selected = days
+        .iter()
+        .copied()
+        .filter(|day| options.days.is_empty() || options.days.contains(&day.nr()))
+        .collect::<Vec<_>>();
+    for (name, _) in &options.params {
+        if !selected
+            .iter()
+            .any(|day| day.params().iter().any(|(param, _)| param == name))
+        {
+            eprintln!(
+                "{} none of the selected days has a parameter named {name}, \
+                 see `list` for the parameters",
+                "error:".bright_red().bold()
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+    let submissions = config.input_dir.value.join("submissions.tsv");
+    let mut inputs = Inputs::new(config);
+    let tasks = selected
+        .iter()
+        .flat_map(|&day| {
+            let variants = match (&options.input, &options.variant) {
+                (Some(_), _) => vec![None],
+                (None, Some(variant)) => vec![variant.clone()],
+                (None, None) => std::iter::once(None)
+                    .chain(inputs.variants(day.nr()).into_iter().map(Some))
+                    .collect(),
+            };
+            variants
+                .into_iter()
+                .map(move |variant| Task { day, variant })
+        })
+        .collect::<Vec<_>>();
+    // Benchmarks run one day at a time by default, to keep the timings clean.
+    let jobs = options.jobs.unwrap_or_else(|| match options.command {
+        Command::Bench => 1,
+        _ => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    });
+    let answers = Answers::new(root.as_ref());
+    let mut prepare = |task: &Task| get_day_input(&mut inputs, &options, task);
+    let mut summaries = Vec::new();
+    match options.command {
+        Command::Run => parallel::run_ordered(
+            &tasks,
+            jobs,
+            &mut prepare,
+            |task, (input, changes)| (exec_day(task.day, input, &options), changes),
+            |task, (output, changes)| {
+                let (is_success, summary) = report_day(
+                    &answers,
+                    &options,
+                    &mut writer,
+                    task,
+                    output,
+                    &changes,
+                    None,
+                );
+                success &= is_success;
+                summaries.push(summary);
+            },
+        ),
+        Command::Bench if !options.scales.is_empty() => {
+            for &day in &selected {
+                success &= bench::sweep_day(&options, &mut writer, day);
+            }
+        }
+        Command::Bench => {
+            let tracker = tracker.as_mut().unwrap();
+            parallel::run_ordered(
+                &tasks,
+                jobs,
+                &mut prepare,
+                |task, (input, changes)| (bench_day(task.day, input, &options), changes),
+                |task, (result, changes)| {
+                    success &= report_bench(&options, tracker, &mut writer, task, result, &changes)
+                },
+            )
+        }
+        Command::List => {
+            for &day in &selected {
+                list_day(&inputs, &mut writer, day);
+            }
+        }
+        Command::History => {
+            for &day in &selected {
+                history_day(&history, &mut writer, day);
+            }
+        }
+        Command::VerifyInputs => {
+            for &day in &selected {
+                success &= inputs::verify_day(&mut inputs, &options, &mut writer, day);
+            }
+        }
+        Command::EncryptInputs => {
+            for &day in &selected {
+                success &= inputs::encrypt_day(&mut inputs, &mut writer, day);
+            }
+        }
+        Command::Examples => {
+            for &day in &selected {
+                success &=
+                    examples::examples_day(&mut inputs, root.as_ref(), &options, &mut writer, day);
+            }
+        }
+        Command::Submit => {
+            let log = SubmissionLog::new(submissions);
+            let client = Box::<http::UreqClient>::default();
+            let submitter = Submitter::new(client, download::BASE_URL, year, log);
+            for &day in &selected {
+                success &= submit::submit_day(&mut inputs, &submitter, &options, &mut writer, day);
+            }
+        }
+    }
+    if options.watch {
+        let files = tasks
+            .iter()
+            .map(|task| {
+                let (day_nr, variant) = (task.day.nr(), task.variant.as_deref());
+                let input = match &options.input {
+                    Some(InputSource::File(path)) => path.clone(),
+                    _ => inputs.stored_path(day_nr, variant),
+                };
+                vec![input, answers.path(day_nr, variant)]
+            })
+            .collect();
+        if is_text {
+            println!("\nWatching for changes, press Ctrl+C to stop.");
+        }
+        watch::watch(files, |changed, paths| {
+            if is_text {
+                watch::print_changes(paths);
+            }
+            for &index in changed {
+                let task = &tasks[index];
+                let (input, changes) = get_day_input(&mut inputs, &options, task);
+                let output = exec_day(task.day, input, &options);
+                let previous = &mut summaries[index];
+                let (_, summary) = report_day(
+                    &answers,
+                    &options,
+                    &mut writer,
+                    task,
+                    output,
+                    &changes,
+                    Some(previous),
+                );
+                *previous = summary;
+            }
+        });
+    }
+    if let Some(Err(e)) = tracker.map(Tracker::finish) {
+        eprintln!("{} {e:#}", "error:".bright_red().bold());
+        success = false;
+    }
+    if is_text {
+        println!();
+    }
 
-    fn print_error(e: anyhow::Error) {
-        println!(" :: {}", format!("error: {}", e).bright_red());
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     }
+}
 
-    fn print_timing(label: &'static str, duration: Duration) {
-        print!(
-            " :: {} {: >13}",
-            label.bright_green(),
-            format!("{duration:?}").white().bold()
-        );
+/// A day, together with the input variant to run it against, where `None` is
+/// the default input.
+struct Task {
+    day: &'static dyn Day,
+    variant: Option<String>,
+}
+
+/// Loads and normalizes the input of a task, also returning the changes that
+/// normalizing made.
+fn get_day_input(
+    inputs: &mut Inputs,
+    options: &Options,
+    task: &Task,
+) -> (Result<Vec<u8>>, Vec<Change>) {
+    let input = match &options.input {
+        Some(source) => inputs.load(source),
+        None => inputs.get(task.day.nr(), task.variant.as_deref()),
+    };
+    normalize_input(task.day.normalization(), input)
+}
+
+pub(crate) fn normalize_input(
+    normalization: Normalization,
+    input: Result<Vec<u8>>,
+) -> (Result<Vec<u8>>, Vec<Change>) {
+    match input {
+        Ok(mut input) => {
+            let changes = normalization.apply(&mut input);
+            (Ok(input), changes)
+        }
+        Err(e) => (Err(e), Vec::new()),
     }
+}
 
-    let day_nr = day.nr();
+pub(crate) fn print_day_header(day_nr: u32, variant: Option<&str>) {
     print!(
         "{} {}",
         "Day".bright_blue(),
         format!("{day_nr:>2}").bright_red().bold()
     );
+    if let Some(variant) = variant {
+        print!(" {}", format!("({variant})").bright_blue());
+    }
+}
+
+fn list_day(inputs: &Inputs, writer: &mut RecordWriter, day: &dyn Day) {
+    let is_cached = inputs.is_cached(day.nr());
+    let variants = inputs.variants(day.nr());
+    let params = params::describe(day.params());
+    writer.write(&output::list_record(
+        day.nr(),
+        is_cached,
+        &variants,
+        &params,
+    ));
+    if writer.is_text() {
+        print_day_header(day.nr(), None);
+        if is_cached {
+            print!(" :: {}", "input cached".bright_green());
+        } else {
+            print!(" :: {}", "input missing".bright_yellow());
+        }
+        if !variants.is_empty() {
+            print!(" :: variants: {}", variants.join(", ").bright_blue());
+        }
+        if !params.is_empty() {
+            print!(" :: params: {}", params.bright_blue());
+        }
+        println!();
+    }
+}
+
+fn history_day(
+    history: &[(Environment, baseline::Entry)],
+    writer: &mut RecordWriter,
+    day: &dyn Day,
+) {
     let day_nr = day.nr();
-    let input = match inputs.get(day_nr) {
-        Ok(x) => x,
-        Err(e) => {
-            print_error(e);
-            return;
+    let mut variants = history
+        .iter()
+        .filter(|(_, entry)| entry.day == day_nr)
+        .map(|(_, entry)| entry.variant.as_deref())
+        .collect::<Vec<_>>();
+    variants.sort_unstable();
+    variants.dedup();
+    if variants.is_empty() {
+        variants.push(None);
+    }
+    for variant in variants {
+        history_variant(history, writer, day_nr, variant);
+    }
+}
+
+fn history_variant(
+    history: &[(Environment, baseline::Entry)],
+    writer: &mut RecordWriter,
+    day_nr: u32,
+    variant: Option<&str>,
+) {
+    // Entries of a single run are appended together, one for each phase.
+    let mut runs: Vec<(&Environment, Vec<baseline::Entry>)> = Vec::new();
+    let entries = history
+        .iter()
+        .filter(|(_, entry)| entry.day == day_nr && entry.variant.as_deref() == variant);
+    for (environment, entry) in entries {
+        match runs.last_mut() {
+            Some((env, entries))
+                if *env == environment && entries.iter().all(|e| e.phase != entry.phase) =>
+            {
+                entries.push(entry.clone())
+            }
+            _ => runs.push((environment, vec![entry.clone()])),
+        }
+    }
+    for (environment, entries) in &runs {
+        writer.write(&output::history_record(day_nr, environment, entries));
+    }
+    if !writer.is_text() {
+        return;
+    }
+
+    print_day_header(day_nr, variant);
+    if runs.is_empty() {
+        println!(" :: {}", "no history".bright_yellow());
+        return;
+    }
+    let plural = if runs.len() == 1 { "" } else { "s" };
+    println!(" :: {} run{plural}", runs.len());
+    let has_prepare = runs
+        .iter()
+        .any(|(_, entries)| entries.iter().any(|e| e.phase == "prepare"));
+    print!("{:>22} {:>14} {:>12}", "", "commit", "parse");
+    if has_prepare {
+        print!(" {:>12}", "prepare");
+    }
+    println!(" {:>12} {:>12} {:>9}", "pt1", "pt2", "change");
+    let total =
+        |entries: &[baseline::Entry]| -> Duration { entries.iter().map(|e| e.stats.median).sum() };
+    let mut previous = None;
+    for (environment, entries) in &runs {
+        let median = |phase: &str| match entries.iter().find(|e| e.phase == phase) {
+            Some(entry) => format!("{:.2?}", entry.stats.median).white().bold(),
+            None => "-".bright_black(),
+        };
+        let total = total(entries);
+        let change = match previous {
+            Some(previous) if !Duration::is_zero(&previous) => {
+                let change = (total.as_nanos() as f64 / previous.as_nanos() as f64 - 1.0) * 100.0;
+                format!("{change:+.1}%").normal()
+            }
+            _ => "".normal(),
+        };
+        print!(
+            "{:>22} {:>14} {:>12}",
+            baseline::format_timestamp(environment.timestamp).bright_blue(),
+            environment.commit,
+            median("parse"),
+        );
+        if has_prepare {
+            print!(" {:>12}", median("prepare"));
+        }
+        println!(" {:>12} {:>12} {:>9}", median("pt1"), median("pt2"), change);
+        previous = Some(total);
+    }
+}
+
+fn report_bench(
+    options: &Options,
+    tracker: &mut Tracker,
+    writer: &mut RecordWriter,
+    task: &Task,
+    result: BenchResult,
+    changes: &[Change],
+) -> bool {
+    let (day_nr, variant) = (task.day.nr(), task.variant.as_deref());
+    let mut success = result.is_success();
+    let mut deltas = Vec::new();
+    if let BenchResult::Ran(summary) = &result {
+        match tracker.record(day_nr, variant, summary) {
+            Ok(x) => deltas = x,
+            Err(e) => {
+                eprintln!("{} {e:#}", "error:".bright_red().bold());
+                success = false;
+            }
+        }
+    }
+    let threshold = options.baseline.threshold;
+    success &= !deltas.iter().any(|delta| delta.is_regression(threshold));
+
+    let mut record = output::bench_record(day_nr, variant, &result, options, &deltas);
+    if options.verbose {
+        record.push(("normalized", normalize::describe(changes).into()));
+    }
+    writer.write(&record);
+    if writer.is_text() {
+        let comparison = tracker.baseline().map(|_| (deltas.as_slice(), threshold));
+        print_day_header(day_nr, variant);
+        print_bench_result(result, options.bench.allocations, comparison);
+        if options.verbose {
+            print_changes(changes);
+        }
+    }
+    success
+}
+
+fn print_baseline_header(baseline: &Baseline) {
+    println!(
+        "Comparing against baseline {}, measured in:",
+        baseline.name.bright_blue().bold()
+    );
+    for env in baseline.environments() {
+        println!(
+            "  {}, commit {}, {}",
+            baseline::format_timestamp(env.timestamp),
+            env.commit,
+            env.machine
+        );
+        println!("  {}", env.rustc.bright_black());
+    }
+    println!();
+}
+
+fn print_bench_result(result: BenchResult, show_allocs: bool, comparison: Option<(&[Delta], f64)>) {
+    let print_stats = |label: &'static str, stats: &Stats, allocs: Option<AllocStats>| {
+        let fmt = |duration: Duration| format!("{duration:.2?}").white().bold();
+        print!(
+            "{:>9} {:>12} {:>12} {:>12} {:>12} {:>12} {:>9}",
+            label.bright_green(),
+            fmt(stats.min),
+            fmt(stats.median),
+            fmt(stats.mean),
+            fmt(stats.p95),
+            fmt(stats.stddev),
+            stats.outliers,
+        );
+        if show_allocs {
+            let fmt = |bytes: u64| format_bytes(bytes as usize).white().bold();
+            match allocs {
+                Some(allocs) => print!(
+                    " {:>9} {:>12} {:>12}",
+                    allocs.count,
+                    fmt(allocs.bytes),
+                    fmt(allocs.peak)
+                ),
+                None => print!(" {:>9} {:>12} {:>12}", "-", "-", "-"),
+            }
+        }
+        if let Some((deltas, threshold)) = comparison {
+            let delta = deltas.iter().find(|delta| delta.phase == label);
+            let change = match delta.and_then(|delta| delta.change) {
+                Some(change) => {
+                    let str = format!("{change:+.1}%");
+                    if change > threshold {
+                        str.bright_red().bold()
+                    } else if change < -threshold {
+                        str.bright_green()
+                    } else {
+                        str.normal()
+                    }
+                }
+                None => "-".bright_black(),
+            };
+            print!(" {change:>12}");
         }
     };
 
-    let start_time = Instant::now();
-    let mut total_timings = match day.exec_bench(&input) {
-        Ok(x) => x,
-        Err(e) => {
-            print_error(e);
+    let summary = match result {
+        BenchResult::NoInput(e) | BenchResult::ParseFailed(e) => {
+            println!(" :: {}", format!("error: {:#}", e).bright_red());
             return;
         }
-    };
-    let mut run_count = 1u32;
-    while run_count < MAX_ITER && Instant::now() - start_time < MAX_TIME {
-        let current_timings = day.exec_bench(&input).unwrap();
-        total_timings.parse += current_timings.parse;
-        total_timings.pt1 += current_timings.pt1;
-        total_timings.pt2 += current_timings.pt2;
-        run_count += 1;
-    }
-
-    let timings = BenchOutputs {
-        parse: total_timings.parse / run_count,
-        pt1: total_timings.pt1 / run_count,
-        pt2: total_timings.pt2 / run_count,
+        BenchResult::Rejected(e) => {
+            println!(" :: {}", format!("invalid input: {:#}", e).bright_red());
+            return;
+        }
+        BenchResult::Aborted(failure) => {
+            println!(" :: {}", failure.to_string().bright_red());
+            return;
+        }
+        BenchResult::Ran(summary) => summary,
     };
 
-    print!(" :: {run_count:>5}");
-    print_timing("parse", timings.parse);
-    print_timing("pt1", timings.pt1);
-    print_timing("pt2", timings.pt2);
+    println!(
+        " :: {} iterations, {} warmup",
+        summary.iterations, summary.warmup_iterations
+    );
+    print!(
+        "{:>9} {:>12} {:>12} {:>12} {:>12} {:>12} {:>9}",
+        "", "min", "median", "mean", "p95", "stddev", "outliers"
+    );
+    if show_allocs {
+        print!(" {:>9} {:>12} {:>12}", "allocs", "allocated", "peak");
+    }
+    if comparison.is_some() {
+        print!(" {:>12}", "vs baseline");
+    }
     println!();
+    print_stats("parse", &summary.parse, summary.parse_allocs);
+    println!();
+    if let Some(prepare) = &summary.prepare {
+        print_stats("prepare", prepare, summary.prepare_allocs);
+        println!();
+    }
+    for (label, pt) in [("pt1", summary.pt1), ("pt2", summary.pt2)] {
+        if let Some(pt) = pt {
+            print_stats(label, &pt.stats, pt.allocs);
+            match pt.output {
+                Ok(_) => println!(),
+                Err(e) => println!(" {}", format!("error: {}", e).bright_red()),
+            }
+        }
+    }
 }
 
-fn exec_day(inputs: &mut Inputs, day: &dyn Day) {
-    let day_nr = day.nr();
-    print!(
-        "{} {}",
-        "Day".bright_blue(),
-        format!("{day_nr:>2}").bright_red().bold()
-    );
+fn sandbox_name(day: &dyn Day) -> String {
+    format!("day {:0>2}", day.nr())
+}
+
+pub(crate) fn bench_day(
+    day: &'static dyn Day,
+    input: Result<Vec<u8>>,
+    options: &Options,
+) -> BenchResult {
+    match input {
+        Ok(input) => {
+            let (part, config) = (options.part, options.bench);
+            // Benchmarks take up to the warmup and measuring time on their own.
+            let limits = Limits {
+                timeout: options
+                    .limits
+                    .timeout
+                    .map(|timeout| timeout + config.warmup + config.max_time),
+                ..options.limits
+            };
+            let overrides = day_params(day, options);
+            sandbox::run(sandbox_name(day), &limits, move || {
+                params::with(day.params(), &overrides, || {
+                    bench::run(day, &input, part, &config)
+                })
+            })
+            .unwrap_or_else(BenchResult::Aborted)
+        }
+        Err(e) => BenchResult::NoInput(e),
+    }
+}
 
-    let result = match inputs.get(day_nr) {
-        Ok(input) => day.exec(&input),
+/// The overridden parameters that the day has, as other days may have been
+/// selected along with it.
+fn day_params(day: &dyn Day, options: &Options) -> Vec<(String, i64)> {
+    let params = day.params();
+    options
+        .params
+        .iter()
+        .filter(|(name, _)| params.iter().any(|(param, _)| param == name))
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn exec_day(
+    day: &'static dyn Day,
+    input: Result<Vec<u8>>,
+    options: &Options,
+) -> (DayResult, Duration) {
+    let start = Instant::now();
+    let result = match input {
+        Ok(input) => {
+            let (part, overrides) = (options.part, day_params(day, options));
+            sandbox::run(sandbox_name(day), &options.limits, move || {
+                params::with(day.params(), &overrides, || day.exec(&input, part))
+            })
+            .unwrap_or_else(DayResult::Aborted)
+        }
         Err(e) => DayResult::NoInput(e),
     };
-    fn err_to_str(e: anyhow::Error) -> ColoredOutput {
-        ColoredOutput {
-            str: e.to_string().red().bold().to_string(),
-            control_char_count: 11,
+    (result, start.elapsed())
+}
+
+/// Verifies and reports the result of a day. When watching, `previous` is the
+/// result of the previous run, which is then shown as a diff.
+fn report_day(
+    answers: &Answers,
+    options: &Options,
+    writer: &mut RecordWriter,
+    task: &Task,
+    (result, duration): (DayResult, Duration),
+    changes: &[Change],
+    previous: Option<&RunSummary>,
+) -> (bool, RunSummary) {
+    let (day_nr, variant) = (task.day.nr(), task.variant.as_deref());
+    let mut success = result.is_success();
+    let mut verifications = [None, None];
+    // Answers of other inputs or parameters can't be verified.
+    let is_verifiable = options.input.is_none() && options.params.is_empty();
+    if let (DayResult::Ran { pt1, pt2 }, true) = (&result, is_verifiable) {
+        fn plain(pt: &Option<Result<Answer>>) -> Option<String> {
+            match pt {
+                Some(Ok(answer)) => Some(render::plain(answer)),
+                _ => None,
+            }
         }
+        let [pt1, pt2] = [plain(pt1), plain(pt2)];
+        let plain = [pt1.as_deref(), pt2.as_deref()];
+        match answers.check(day_nr, variant, plain, options.record) {
+            Ok(x) => verifications = x,
+            Err(e) => {
+                eprintln!("{} {e:#}", "error:".bright_red().bold());
+                success = false;
+            }
+        }
+    }
+    success &= !verifications
+        .iter()
+        .flatten()
+        .any(|v| matches!(v, Verification::Mismatch { .. }));
+
+    let mut record = output::run_record(day_nr, variant, &result, &verifications);
+    if options.verbose {
+        record.push(("normalized", normalize::describe(changes).into()));
+    }
+    writer.write(&record);
+    let summary = RunSummary::new(&result, duration);
+    match (writer.is_text(), previous) {
+        (false, _) => {}
+        (true, Some(previous)) => {
+            watch::print_diff(day_nr, variant, previous, &summary, &verifications)
+        }
+        (true, None) => {
+            print_day_header(day_nr, variant);
+            print_day_result(result, verifications);
+            if options.verbose {
+                print_changes(changes);
+            }
+        }
+    }
+    (success, summary)
+}
+
+fn print_changes(changes: &[Change]) {
+    if !changes.is_empty() {
+        let description = format!("normalized: {}", normalize::describe(changes));
+        println!("        {}", description.bright_black());
+    }
+}
+
+fn verification_marker(verification: &Verification) -> String {
+    match verification {
+        Verification::Verified => "✓".bright_green().to_string(),
+        Verification::Mismatch { expected } => format!(
+            "{} {}",
+            "✗".bright_red().bold(),
+            format!("(expected {expected})").bright_red()
+        ),
+        Verification::Unknown => "?".bright_black().to_string(),
+        Verification::Recorded => "●".bright_blue().to_string(),
+    }
+}
+
+fn print_day_result(result: DayResult, verifications: [Option<Verification>; 2]) {
+    fn err_to_str(e: anyhow::Error) -> String {
+        format!("{e:#}").red().bold().to_string()
     }
-    fn fmt_output(result: Result<ColoredOutput>) -> ColoredOutput {
-        result.unwrap_or_else(err_to_str)
+    fn fmt_output(result: Result<Answer>) -> String {
+        result.map_or_else(err_to_str, |answer| render::terminal(&answer))
     }
-    let (pt1_key, pt1_value, pt2_value) = match result {
-        DayResult::NoInput(e) => ("no input".bright_red(), err_to_str(e), None),
-        DayResult::ParseFailed(e) => ("parse error".bright_red(), err_to_str(e), None),
-        DayResult::Ran { pt1, pt2 } => {
-            ("pt1".bright_green(), fmt_output(pt1), Some(fmt_output(pt2)))
+    type Output = (ColoredString, String, Option<Verification>);
+    let outputs: Vec<Output> = match result {
+        DayResult::NoInput(e) => vec![("no input".bright_red(), err_to_str(e), None)],
+        DayResult::Rejected(e) => vec![("invalid input".bright_red(), err_to_str(e), None)],
+        DayResult::ParseFailed(e) => vec![("parse error".bright_red(), err_to_str(e), None)],
+        DayResult::Aborted(failure) => {
+            vec![("aborted".bright_red(), err_to_str(failure.into()), None)]
         }
+        DayResult::Ran { pt1, pt2 } => [("pt1", pt1), ("pt2", pt2)]
+            .into_iter()
+            .zip(verifications)
+            .filter_map(|((key, value), verification)| {
+                Some((key.bright_green(), fmt_output(value?), verification))
+            })
+            .collect(),
     };
-    let contains_newlines = pt1_value.str.contains('\n')
-        || if let Some(v) = &pt2_value {
-            v.str.contains('\n')
-        } else {
-            false
-        };
+    let contains_newlines = outputs.iter().any(|(_, value, _)| value.contains('\n'));
     const COLUMN_WIDTH: usize = 80;
     const OVERHEAD_WIDTH: usize = 22;
     const PT_WIDHT: usize = (COLUMN_WIDTH - OVERHEAD_WIDTH) / 2;
@@ -183,32 +856,35 @@ fn exec_day(inputs: &mut Inputs, day: &dyn Day) {
             );
         }
         println!();
-        print_key(&pt1_key);
-        println!("{}", pt1_value.str);
-        if let Some(pt2_value) = pt2_value {
-            print_key(&"pt2".bright_green());
-            println!("{}", pt2_value.str);
+        for (key, value, verification) in &outputs {
+            print_key(key);
+            println!("{value}");
+            if let Some(verification) = verification {
+                println!("{}", verification_marker(verification));
+            }
         }
         println!("{:-<width$}", "", width = COLUMN_WIDTH);
     } else {
-        print!(
-            " :: {} {: >width$}",
-            pt1_key,
-            pt1_value.str,
-            width = PT_WIDHT + pt1_value.control_char_count,
-        );
-        if let Some(pt2_value) = pt2_value {
-            print!(
-                " :: {} {: >width$}",
-                "pt2".bright_green(),
-                pt2_value.str,
-                width = PT_WIDHT + pt2_value.control_char_count,
-            );
+        for (key, value, verification) in &outputs {
+            let padding = PT_WIDHT.saturating_sub(render::visible_width(value));
+            print!(" :: {key} {:padding$}{value}", "");
+            if let Some(verification) = verification {
+                print!(" {}", verification_marker(verification));
+            }
         }
         println!();
     }
 }
 
-pub fn get_input(day_nr: u32) -> Result<Vec<u8>> {
-    Inputs::new().get(day_nr)
+/// Loads the input of a day, configured the same way as when running the
+/// command line interface without any arguments, with the default
+/// normalization.
+pub fn get_input(year: u32, root: impl AsRef<Path>, day_nr: u32) -> Result<Vec<u8>> {
+    let options = match cli::parse_args(std::iter::empty()) {
+        Ok(Cli::Options(options)) => options,
+        _ => unreachable!(),
+    };
+    let mut input = Inputs::new(Config::new(root.as_ref(), year, &options)?).get(day_nr, None)?;
+    Normalization::default().apply(&mut input);
+    Ok(input)
 }
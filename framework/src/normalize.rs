@@ -0,0 +1,184 @@
+use std::fmt::{self, Display};
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// The steps used to clean up an input before it is parsed, so parsers only
+/// have to deal with `\n` separated lines that end in a single newline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    /// Converts `\r\n` and lone `\r` line endings to `\n`.
+    pub line_endings: bool,
+    /// Removes a UTF-8 byte order mark from the start.
+    pub bom: bool,
+    /// Removes spaces and tabs from the end of every line.
+    pub trailing_whitespace: bool,
+    /// Ends a non-empty input with exactly one newline.
+    pub final_newline: bool,
+}
+
+impl Normalization {
+    pub const ALL: Normalization = Normalization {
+        line_endings: true,
+        bom: true,
+        trailing_whitespace: true,
+        final_newline: true,
+    };
+    pub const NONE: Normalization = Normalization {
+        line_endings: false,
+        bom: false,
+        trailing_whitespace: false,
+        final_newline: false,
+    };
+
+    /// Normalizes the input in place, and returns what was changed.
+    pub fn apply(self, input: &mut Vec<u8>) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if self.bom && input.starts_with(BOM) {
+            input.drain(..BOM.len());
+            changes.push(Change::Bom);
+        }
+        if self.line_endings && input.contains(&b'\r') {
+            let mut count = 0;
+            let mut normalized = Vec::with_capacity(input.len());
+            let mut bytes = input.iter().copied().peekable();
+            while let Some(c) = bytes.next() {
+                if c == b'\r' {
+                    bytes.next_if_eq(&b'\n');
+                    normalized.push(b'\n');
+                    count += 1;
+                } else {
+                    normalized.push(c);
+                }
+            }
+            *input = normalized;
+            changes.push(Change::LineEndings(count));
+        }
+        if self.trailing_whitespace {
+            let mut count = 0;
+            let mut normalized = Vec::with_capacity(input.len());
+            for (i, line) in input.split(|&c| c == b'\n').enumerate() {
+                if i > 0 {
+                    normalized.push(b'\n');
+                }
+                let len = line
+                    .iter()
+                    .rposition(|c| !matches!(c, b' ' | b'\t'))
+                    .map_or(0, |i| i + 1);
+                count += usize::from(len < line.len());
+                normalized.extend_from_slice(&line[..len]);
+            }
+            if count > 0 {
+                *input = normalized;
+                changes.push(Change::TrailingWhitespace(count));
+            }
+        }
+        if self.final_newline && !input.is_empty() {
+            let newlines = input.iter().rev().take_while(|&&c| c == b'\n').count();
+            input.truncate(input.len() - newlines);
+            match newlines {
+                0 => changes.push(Change::FinalNewline),
+                1 => {}
+                _ => changes.push(Change::BlankLines(newlines - 1)),
+            }
+            if !input.is_empty() {
+                input.push(b'\n');
+            }
+        }
+        changes
+    }
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization::ALL
+    }
+}
+
+/// A change made to an input while normalizing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Bom,
+    LineEndings(usize),
+    /// Amount of lines that had trailing whitespace.
+    TrailingWhitespace(usize),
+    BlankLines(usize),
+    FinalNewline,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = |count: usize| if count == 1 { "" } else { "s" };
+        match *self {
+            Change::Bom => write!(f, "removed the byte order mark"),
+            Change::LineEndings(n) => write!(f, "converted {n} line ending{}", s(n)),
+            Change::TrailingWhitespace(n) => {
+                write!(f, "removed trailing whitespace from {n} line{}", s(n))
+            }
+            Change::BlankLines(n) => write!(f, "removed {n} trailing blank line{}", s(n)),
+            Change::FinalNewline => write!(f, "added a final newline"),
+        }
+    }
+}
+
+/// Describes all changes on one line, separated by commas.
+pub fn describe(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(Change::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(normalization: Normalization, input: &[u8]) -> (Vec<u8>, Vec<Change>) {
+        let mut input = input.to_vec();
+        let changes = normalization.apply(&mut input);
+        (input, changes)
+    }
+
+    #[test]
+    fn normalization() {
+        let clean = b"1 2\n3 4\n";
+        assert_eq!(
+            (clean.to_vec(), vec![]),
+            normalize(Normalization::ALL, clean)
+        );
+        assert_eq!((vec![], vec![]), normalize(Normalization::ALL, b""));
+
+        let messy = b"\xEF\xBB\xBF1 2 \r\n3 4\t\r\n\r\n\n";
+        assert_eq!(
+            (
+                clean.to_vec(),
+                vec![
+                    Change::Bom,
+                    Change::LineEndings(3),
+                    Change::TrailingWhitespace(2),
+                    Change::BlankLines(2),
+                ]
+            ),
+            normalize(Normalization::ALL, messy)
+        );
+        assert_eq!(
+            (messy.to_vec(), vec![]),
+            normalize(Normalization::NONE, messy)
+        );
+        assert_eq!(
+            (
+                b"a\nb\n".to_vec(),
+                vec![Change::LineEndings(1), Change::FinalNewline]
+            ),
+            normalize(Normalization::ALL, b"a\rb")
+        );
+        let keep_whitespace = Normalization {
+            trailing_whitespace: false,
+            ..Normalization::ALL
+        };
+        assert_eq!(
+            (b"  \n".to_vec(), vec![Change::BlankLines(1)]),
+            normalize(keep_whitespace, b"  \n\n")
+        );
+    }
+}
@@ -0,0 +1,636 @@
+use crate::{
+    allocator::AllocStats,
+    answers::Verification,
+    baseline::{Delta, Entry, Environment},
+    bench::{BenchResult, BenchSummary, PartSummary, Stats},
+    cli::{Command, Options},
+    day::{Answer, DayResult, Part},
+    examples::Example,
+    inputs::CacheStatus,
+    render,
+    submit::Reply,
+};
+use std::{fmt::Write, path::PathBuf, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn is_text(self) -> bool {
+        self == OutputFormat::Text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Int(u128),
+    Float(f64),
+    Str(String),
+    /// Rendered as a number or a string, depending on the answer.
+    Answer(Answer),
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Int(value as u128)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Int(value as u128)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(value: Duration) -> Self {
+        Value::Int(value.as_nanos())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+/// A flat, ordered list of fields. All records emitted by one command must
+/// have the same keys, so that they line up with the CSV header.
+pub type Record = Vec<(&'static str, Value)>;
+
+pub struct RecordWriter {
+    format: OutputFormat,
+    header_written: bool,
+}
+
+impl RecordWriter {
+    pub fn new(format: OutputFormat) -> RecordWriter {
+        RecordWriter {
+            format,
+            header_written: false,
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format.is_text()
+    }
+
+    pub fn write(&mut self, record: &Record) {
+        let line = match self.format {
+            OutputFormat::Text => return,
+            OutputFormat::Json => to_json(record),
+            OutputFormat::Csv => {
+                if !self.header_written {
+                    self.header_written = true;
+                    let header = record.iter().map(|(key, _)| csv_escape(key));
+                    println!("{}", header.collect::<Vec<_>>().join(","));
+                }
+                to_csv(record)
+            }
+        };
+        println!("{line}");
+    }
+}
+
+fn to_json(record: &Record) -> String {
+    let mut line = String::from("{");
+    for (i, (key, value)) in record.iter().enumerate() {
+        if i != 0 {
+            line.push(',');
+        }
+        render::json_string_into(&mut line, key);
+        line.push(':');
+        match value {
+            Value::Null => line.push_str("null"),
+            Value::Int(x) => write!(line, "{x}").unwrap(),
+            Value::Float(x) if x.is_finite() => write!(line, "{x}").unwrap(),
+            Value::Float(_) => line.push_str("null"),
+            Value::Str(x) => render::json_string_into(&mut line, x),
+            Value::Answer(x) => line.push_str(&render::json(x)),
+        }
+    }
+    line.push('}');
+    line
+}
+
+fn to_csv(record: &Record) -> String {
+    let fields = record.iter().map(|(_, value)| match value {
+        Value::Null => String::new(),
+        Value::Int(x) => x.to_string(),
+        Value::Float(x) => x.to_string(),
+        Value::Str(x) => csv_escape(x),
+        Value::Answer(x) => csv_escape(&render::plain(x)),
+    });
+    fields.collect::<Vec<_>>().join(",")
+}
+
+fn csv_escape(str: &str) -> String {
+    if str.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", str.replace('"', "\"\""))
+    } else {
+        str.to_owned()
+    }
+}
+
+fn split_output(output: Option<&anyhow::Result<Answer>>) -> (Value, Value) {
+    match output {
+        None => (Value::Null, Value::Null),
+        Some(Ok(answer)) => (Value::Answer(answer.clone()), Value::Null),
+        Some(Err(e)) => (Value::Null, e.to_string().into()),
+    }
+}
+
+pub fn not_implemented_record(day: u32, options: &Options) -> Record {
+    let command = options.command;
+    let mut record = vec![("day", day.into())];
+    if command == Command::Bench && !options.scales.is_empty() {
+        record.extend(scale_fields(None, None));
+    } else if matches!(command, Command::Run | Command::Bench | Command::History) {
+        record.push(("variant", Value::Null));
+    }
+    record.push(("status", "not_implemented".into()));
+    match command {
+        Command::List => record.extend([
+            ("input", Value::Null),
+            ("variants", Value::Null),
+            ("params", Value::Null),
+        ]),
+        Command::History => record.extend(history_fields(None, None)),
+        Command::VerifyInputs => record.extend(verify_fields(None, None)),
+        Command::EncryptInputs => record.extend(encrypt_fields(None)),
+        Command::Examples => record.extend(example_fields(None, None, None)),
+        Command::Submit => record.extend(submit_fields(None, None)),
+        Command::Run | Command::Bench => {
+            record.push(("error", Value::Null));
+            if command == Command::Bench {
+                record.extend(bench_fields(None));
+                if options.bench.allocations {
+                    record.extend(alloc_fields(None));
+                }
+                if options.baseline.compare.is_some() {
+                    record.extend(delta_fields(&[]));
+                }
+            }
+            record.extend(part_fields(None, None));
+            if command == Command::Run {
+                record.extend(verification_fields(&[None, None]));
+            }
+            if options.verbose {
+                record.push(("normalized", Value::Null));
+            }
+        }
+    }
+    record
+}
+
+/// Creates the record for a listed day, with its named input variants
+/// separated by spaces.
+pub fn list_record(day: u32, is_cached: bool, variants: &[String], params: &str) -> Record {
+    vec![
+        ("day", day.into()),
+        ("status", "ok".into()),
+        ("input", if is_cached { "cached" } else { "missing" }.into()),
+        ("variants", variants.join(" ").into()),
+        ("params", params.into()),
+    ]
+}
+
+pub fn run_record(
+    day: u32,
+    variant: Option<&str>,
+    result: &DayResult,
+    verifications: &[Option<Verification>; 2],
+) -> Record {
+    let (status, error, pt1, pt2) = match result {
+        DayResult::NoInput(e) => ("no_input", Some(format!("{e:#}")), None, None),
+        DayResult::Rejected(e) => ("rejected", Some(format!("{e:#}")), None, None),
+        DayResult::ParseFailed(e) => ("parse_failed", Some(e.to_string()), None, None),
+        DayResult::Aborted(failure) => (failure.name(), Some(failure.to_string()), None, None),
+        DayResult::Ran { pt1, pt2 } => (
+            if result.is_success() { "ok" } else { "failed" },
+            None,
+            pt1.as_ref(),
+            pt2.as_ref(),
+        ),
+    };
+    let mut record = vec![
+        ("day", day.into()),
+        ("variant", variant.into()),
+        ("status", status.into()),
+        ("error", error.into()),
+    ];
+    record.extend(part_fields(pt1, pt2));
+    record.extend(verification_fields(verifications));
+    record
+}
+
+/// Creates the record for a benchmarked day, where `deltas` are the changes
+/// compared to the baseline, if any.
+pub fn bench_record(
+    day: u32,
+    variant: Option<&str>,
+    result: &BenchResult,
+    options: &Options,
+    deltas: &[Delta],
+) -> Record {
+    let (status, error, summary) = match result {
+        BenchResult::NoInput(e) => ("no_input", Some(format!("{e:#}")), None),
+        BenchResult::Rejected(e) => ("rejected", Some(format!("{e:#}")), None),
+        BenchResult::ParseFailed(e) => ("parse_failed", Some(e.to_string()), None),
+        BenchResult::Aborted(failure) => (failure.name(), Some(failure.to_string()), None),
+        BenchResult::Ran(summary) => (
+            if result.is_success() { "ok" } else { "failed" },
+            None,
+            Some(&**summary),
+        ),
+    };
+    let mut record = vec![
+        ("day", day.into()),
+        ("variant", variant.into()),
+        ("status", status.into()),
+        ("error", error.into()),
+    ];
+    record.extend(bench_fields(summary));
+    if options.bench.allocations {
+        record.extend(alloc_fields(summary));
+    }
+    if options.baseline.compare.is_some() {
+        record.extend(delta_fields(deltas));
+    }
+    fn output(pt: &Option<PartSummary>) -> Option<&anyhow::Result<Answer>> {
+        pt.as_ref().map(|pt| &pt.output)
+    }
+    record.extend(part_fields(
+        summary.and_then(|summary| output(&summary.pt1)),
+        summary.and_then(|summary| output(&summary.pt2)),
+    ));
+    record
+}
+
+/// Creates the record for a day benchmarked against a generated input, where
+/// the input is `None` when the day has no generator.
+pub fn scale_record(
+    day: u32,
+    scale: u32,
+    input_bytes: Option<usize>,
+    result: &BenchResult,
+    options: &Options,
+) -> Record {
+    let mut record = bench_record(day, None, result, options, &[]);
+    // The scale and input size take the place of the variant.
+    record.splice(1..2, scale_fields(Some(scale), input_bytes));
+    // Generated inputs aren't normalized.
+    if options.verbose {
+        record.push(("normalized", Value::Null));
+    }
+    record
+}
+
+fn scale_fields(scale: Option<u32>, input_bytes: Option<usize>) -> Record {
+    vec![
+        ("scale", scale.into()),
+        ("input_bytes", input_bytes.map(|bytes| bytes as u64).into()),
+    ]
+}
+
+fn bench_fields(summary: Option<&BenchSummary>) -> Record {
+    fn stats_fields(record: &mut Record, keys: [&'static str; 6], stats: Option<&Stats>) {
+        let [min, median, mean, p95, stddev, outliers] = keys;
+        record.extend([
+            (min, stats.map(|s| s.min).into()),
+            (median, stats.map(|s| s.median).into()),
+            (mean, stats.map(|s| s.mean).into()),
+            (p95, stats.map(|s| s.p95).into()),
+            (stddev, stats.map(|s| s.stddev).into()),
+            (outliers, stats.map(|s| s.outliers).into()),
+        ]);
+    }
+    fn stats(pt: &Option<PartSummary>) -> Option<&Stats> {
+        pt.as_ref().map(|pt| &pt.stats)
+    }
+
+    let mut record = vec![
+        ("iterations", summary.map(|s| s.iterations).into()),
+        (
+            "warmup_iterations",
+            summary.map(|s| s.warmup_iterations).into(),
+        ),
+    ];
+    #[rustfmt::skip]
+    let phases = [
+        (["parse_min_ns", "parse_median_ns", "parse_mean_ns", "parse_p95_ns", "parse_stddev_ns", "parse_outliers"], summary.map(|s| &s.parse)),
+        (["prepare_min_ns", "prepare_median_ns", "prepare_mean_ns", "prepare_p95_ns", "prepare_stddev_ns", "prepare_outliers"], summary.and_then(|s| s.prepare.as_ref())),
+        (["pt1_min_ns", "pt1_median_ns", "pt1_mean_ns", "pt1_p95_ns", "pt1_stddev_ns", "pt1_outliers"], summary.and_then(|s| stats(&s.pt1))),
+        (["pt2_min_ns", "pt2_median_ns", "pt2_mean_ns", "pt2_p95_ns", "pt2_stddev_ns", "pt2_outliers"], summary.and_then(|s| stats(&s.pt2))),
+    ];
+    for (keys, stats) in phases {
+        stats_fields(&mut record, keys, stats);
+    }
+    record
+}
+
+fn alloc_fields(summary: Option<&BenchSummary>) -> Record {
+    fn allocs(pt: &Option<PartSummary>) -> Option<AllocStats> {
+        pt.as_ref().and_then(|pt| pt.allocs)
+    }
+    let mut record = Record::new();
+    #[rustfmt::skip]
+    let phases = [
+        (["parse_allocs", "parse_alloc_bytes", "parse_peak_bytes"], summary.and_then(|s| s.parse_allocs)),
+        (["prepare_allocs", "prepare_alloc_bytes", "prepare_peak_bytes"], summary.and_then(|s| s.prepare_allocs)),
+        (["pt1_allocs", "pt1_alloc_bytes", "pt1_peak_bytes"], summary.and_then(|s| allocs(&s.pt1))),
+        (["pt2_allocs", "pt2_alloc_bytes", "pt2_peak_bytes"], summary.and_then(|s| allocs(&s.pt2))),
+    ];
+    for ([count, bytes, peak], allocs) in phases {
+        record.extend([
+            (count, allocs.map(|a| a.count).into()),
+            (bytes, allocs.map(|a| a.bytes).into()),
+            (peak, allocs.map(|a| a.peak).into()),
+        ]);
+    }
+    record
+}
+
+fn delta_fields(deltas: &[Delta]) -> Record {
+    let mut record = Record::new();
+    for (phase, keys) in [
+        ("parse", ["parse_baseline_median_ns", "parse_change_pct"]),
+        (
+            "prepare",
+            ["prepare_baseline_median_ns", "prepare_change_pct"],
+        ),
+        ("pt1", ["pt1_baseline_median_ns", "pt1_change_pct"]),
+        ("pt2", ["pt2_baseline_median_ns", "pt2_change_pct"]),
+    ] {
+        let delta = deltas.iter().find(|delta| delta.phase == phase);
+        record.extend([
+            (keys[0], delta.map(|d| d.baseline).into()),
+            (keys[1], delta.and_then(|d| d.change).into()),
+        ]);
+    }
+    record
+}
+
+/// Creates the record for the cached input of a day, where `refetch` is the
+/// result of downloading it again, if that was attempted.
+pub fn verify_record(
+    day: u32,
+    status: &CacheStatus,
+    refetch: Option<&Result<(), String>>,
+) -> Record {
+    let mut record = vec![("day", day.into()), ("status", status.name().into())];
+    record.extend(verify_fields(Some(status), refetch));
+    record
+}
+
+/// Creates the record for the migration of the plaintext inputs of a day to
+/// encrypted ones.
+pub fn encrypt_record(day: u32, result: &Result<Vec<PathBuf>, String>) -> Record {
+    let status = if result.is_ok() { "ok" } else { "failed" };
+    let mut record = vec![("day", day.into()), ("status", status.into())];
+    record.extend(encrypt_fields(Some(result)));
+    record
+}
+
+/// Creates the record for an example from the puzzle description of a day,
+/// where `constant` is the name it was added to the tests as, if it was.
+pub fn example_record(
+    day: u32,
+    nr: usize,
+    example: &Example,
+    answer: Option<&str>,
+    constant: Option<&str>,
+) -> Record {
+    let mut record = vec![("day", day.into()), ("status", "ok".into())];
+    record.extend(example_fields(Some((nr, example, answer)), constant, None));
+    record
+}
+
+/// Creates the record for a day of which the examples could not be extracted.
+pub fn examples_failed_record(day: u32, error: &str) -> Record {
+    let mut record = vec![("day", day.into()), ("status", "failed".into())];
+    record.extend(example_fields(None, None, Some(error)));
+    record
+}
+
+fn example_fields(
+    example: Option<(usize, &Example, Option<&str>)>,
+    constant: Option<&str>,
+    error: Option<&str>,
+) -> Record {
+    let part = |part| if part == Part::One { 1u32 } else { 2 };
+    vec![
+        ("nr", example.map(|(nr, _, _)| nr as u64).into()),
+        (
+            "part",
+            example.map(|(_, example, _)| part(example.part)).into(),
+        ),
+        ("answer", example.and_then(|(_, _, answer)| answer).into()),
+        (
+            "text",
+            example.map(|(_, example, _)| example.text.as_str()).into(),
+        ),
+        ("constant", constant.into()),
+        ("error", error.into()),
+    ]
+}
+
+/// Creates the record for the submission of the answer to a part of a day,
+/// where `answer` is `None` when no answer could be computed.
+pub fn submit_record(
+    day: u32,
+    part: u32,
+    answer: Option<&str>,
+    result: &Result<Reply, String>,
+) -> Record {
+    let status = if result.is_ok() { "ok" } else { "failed" };
+    let mut record = vec![("day", day.into()), ("status", status.into())];
+    record.extend(submit_fields(Some((part, answer)), Some(result)));
+    record
+}
+
+fn submit_fields(
+    submission: Option<(u32, Option<&str>)>,
+    result: Option<&Result<Reply, String>>,
+) -> Record {
+    let (part, answer) = submission.map_or((None, None), |(part, answer)| (Some(part), answer));
+    let (reply, error) = match result {
+        None => (None, None),
+        Some(Ok(reply)) => (Some(reply), None),
+        Some(Err(e)) => (None, Some(e.as_str())),
+    };
+    vec![
+        ("part", part.into()),
+        ("answer", answer.into()),
+        ("verdict", reply.map(|reply| reply.verdict.name()).into()),
+        ("cooldown", reply.map(|reply| reply.cooldown).into()),
+        ("error", error.into()),
+    ]
+}
+
+fn encrypt_fields(result: Option<&Result<Vec<PathBuf>, String>>) -> Record {
+    let (files, error) = match result {
+        None => (None, None),
+        Some(Ok(paths)) => {
+            let paths = paths.iter().map(|path| path.display().to_string());
+            (Some(paths.collect::<Vec<_>>().join(" ")), None)
+        }
+        Some(Err(e)) => (None, Some(e.as_str())),
+    };
+    vec![("files", files.into()), ("error", error.into())]
+}
+
+fn verify_fields(status: Option<&CacheStatus>, refetch: Option<&Result<(), String>>) -> Record {
+    let reason = match status {
+        Some(CacheStatus::Broken(reason)) => Some(*reason),
+        Some(CacheStatus::Unreadable(reason)) => Some(reason.as_str()),
+        _ => None,
+    };
+    let metadata = status.and_then(CacheStatus::metadata);
+    let (refetched, refetch_error) = match refetch {
+        None => (None, None),
+        Some(Ok(())) => (Some("ok"), None),
+        Some(Err(e)) => (Some("failed"), Some(e.as_str())),
+    };
+    vec![
+        ("reason", reason.into()),
+        ("downloaded", metadata.map(|m| m.downloaded).into()),
+        ("checksum", metadata.map(|m| m.checksum.as_str()).into()),
+        ("source", metadata.map(|m| m.source.as_str()).into()),
+        ("refetch", refetched.into()),
+        ("refetch_error", refetch_error.into()),
+    ]
+}
+
+/// Creates the record for a single benchmark run of a day in the history.
+pub fn history_record(day: u32, environment: &Environment, entries: &[Entry]) -> Record {
+    let variant = entries.first().and_then(|e| e.variant.as_deref());
+    let mut record = vec![
+        ("day", day.into()),
+        ("variant", variant.into()),
+        ("status", "ok".into()),
+    ];
+    record.extend(history_fields(Some(environment), Some(entries)));
+    record
+}
+
+fn history_fields(environment: Option<&Environment>, entries: Option<&[Entry]>) -> Record {
+    let median = |phase: &str| -> Value {
+        let entry = entries.and_then(|entries| entries.iter().find(|e| e.phase == phase));
+        entry.map(|e| e.stats.median).into()
+    };
+    vec![
+        ("timestamp", environment.map(|e| e.timestamp).into()),
+        ("commit", environment.map(|e| e.commit.as_str()).into()),
+        ("rustc", environment.map(|e| e.rustc.as_str()).into()),
+        ("machine", environment.map(|e| e.machine.as_str()).into()),
+        (
+            "iterations",
+            entries.and_then(|e| e.first()).map(|e| e.iterations).into(),
+        ),
+        ("parse_median_ns", median("parse")),
+        ("prepare_median_ns", median("prepare")),
+        ("pt1_median_ns", median("pt1")),
+        ("pt2_median_ns", median("pt2")),
+    ]
+}
+
+fn part_fields(
+    pt1: Option<&anyhow::Result<Answer>>,
+    pt2: Option<&anyhow::Result<Answer>>,
+) -> Record {
+    let (pt1, pt1_error) = split_output(pt1);
+    let (pt2, pt2_error) = split_output(pt2);
+    vec![
+        ("pt1", pt1),
+        ("pt1_error", pt1_error),
+        ("pt2", pt2),
+        ("pt2_error", pt2_error),
+    ]
+}
+
+fn verification_fields([pt1, pt2]: &[Option<Verification>; 2]) -> Record {
+    fn name(v: &Option<Verification>) -> Option<&'static str> {
+        v.as_ref().map(Verification::name)
+    }
+    fn expected(v: &Option<Verification>) -> Option<&str> {
+        v.as_ref().and_then(Verification::expected)
+    }
+    vec![
+        ("pt1_verification", name(pt1).into()),
+        ("pt1_expected", expected(pt1).into()),
+        ("pt2_verification", name(pt2).into()),
+        ("pt2_expected", expected(pt2).into()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cli::{parse_args, Cli},
+        day::ToAnswer,
+        submissions::MulSubmission,
+    };
+
+    #[test]
+    fn escaping() {
+        let record: Record = vec![
+            ("day", 13u32.into()),
+            ("status", "ok".into()),
+            ("error", Value::Null),
+            ("pt1", "#..#\n\"a\", b".into()),
+            ("pt2", Value::Answer(MulSubmission(3i64, -4).to_answer())),
+        ];
+        assert_eq!(
+            r##"{"day":13,"status":"ok","error":null,"pt1":"#..#\n\"a\", b","pt2":-12}"##,
+            to_json(&record)
+        );
+        assert_eq!("13,ok,,\"#..#\n\"\"a\"\", b\",-12", to_csv(&record));
+    }
+
+    #[test]
+    fn same_columns() {
+        let options = |args: &[&str]| match parse_args(args.iter().map(|&s| s.to_owned())) {
+            Ok(Cli::Options(options)) => *options,
+            x => panic!("expected options, got: {x:?}"),
+        };
+        let keys = |record: Record| record.into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+
+        let run = options(&["run", "--verbose"]);
+        let result = DayResult::NoInput(anyhow::anyhow!("missing"));
+        let mut record = run_record(1, None, &result, &[None, None]);
+        record.push(("normalized", Value::Null));
+        assert_eq!(keys(record), keys(not_implemented_record(1, &run)));
+
+        let sweep = options(&["bench", "--scales", "1", "--verbose"]);
+        let result = BenchResult::NoInput(anyhow::anyhow!("missing"));
+        assert_eq!(
+            keys(scale_record(1, 1, None, &result, &sweep)),
+            keys(not_implemented_record(1, &sweep))
+        );
+    }
+}
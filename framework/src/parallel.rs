@@ -0,0 +1,108 @@
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+/// Runs `execute` for every item on up to `jobs` threads. `prepare` and
+/// `report` are called on the current thread, in the order of the items, so
+/// results are reported as soon as they and all the items before them are
+/// done.
+pub fn run_ordered<T, I, O>(
+    items: &[T],
+    jobs: usize,
+    mut prepare: impl FnMut(&T) -> I,
+    execute: impl Fn(&T, I) -> O + Sync,
+    mut report: impl FnMut(&T, O),
+) where
+    T: Sync,
+    I: Send,
+    O: Send,
+{
+    let jobs = jobs.min(items.len());
+    if jobs <= 1 {
+        for item in items {
+            let input = prepare(item);
+            report(item, execute(item, input));
+        }
+        return;
+    }
+
+    let (work_tx, work_rx) = mpsc::channel::<(usize, I)>();
+    let (result_tx, result_rx) = mpsc::channel::<(usize, O)>();
+    let work_rx = Mutex::new(work_rx);
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let (work_rx, result_tx, execute) = (&work_rx, result_tx.clone(), &execute);
+            scope.spawn(move || loop {
+                let work = work_rx.lock().unwrap().recv();
+                let (index, input) = match work {
+                    Ok(x) => x,
+                    Err(_) => break,
+                };
+                if result_tx
+                    .send((index, execute(&items[index], input)))
+                    .is_err()
+                {
+                    break;
+                }
+            });
+        }
+        drop(result_tx);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let mut report_ready = |pending: &mut BTreeMap<usize, O>| {
+            while let Some(output) = pending.remove(&next) {
+                report(&items[next], output);
+                next += 1;
+            }
+        };
+        // Inputs are prepared one at a time, so that downloads stay
+        // sequential, while reporting whatever finished in the meantime.
+        for (index, item) in items.iter().enumerate() {
+            let _ = work_tx.send((index, prepare(item)));
+            pending.extend(result_rx.try_iter());
+            report_ready(&mut pending);
+        }
+        drop(work_tx);
+        for (index, output) in result_rx {
+            pending.insert(index, output);
+            report_ready(&mut pending);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_in_order() {
+        let items = (0..20u64).collect::<Vec<_>>();
+        for jobs in [1, 4, 32] {
+            let mut prepared = Vec::new();
+            let mut reported = Vec::new();
+            run_ordered(
+                &items,
+                jobs,
+                |&item| {
+                    prepared.push(item);
+                    item * 10
+                },
+                |&item, input| {
+                    // Later items finish first.
+                    thread::sleep(Duration::from_millis(20 - item));
+                    input + 1
+                },
+                |&item, output| reported.push((item, output)),
+            );
+            assert_eq!(items, prepared);
+            assert_eq!(
+                items.iter().map(|&i| (i, i * 10 + 1)).collect::<Vec<_>>(),
+                reported
+            );
+        }
+    }
+}
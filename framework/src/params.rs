@@ -0,0 +1,81 @@
+//! Named parameters of days, such as an amount of steps, that default to the
+//! values of the puzzle, and can be overridden with `--param NAME=VALUE`.
+
+use std::cell::RefCell;
+
+/// A parameter of a day, its name and default value, declared with
+/// `day!(..., params = &[("steps", 10)])`.
+pub type Param = (&'static str, i64);
+
+thread_local! {
+    static VALUES: RefCell<Vec<(&'static str, i64)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with the parameters of a day set to their defaults, except for
+/// those in `overrides`. Panics when an override isn't one of the parameters.
+pub fn with<T>(
+    params: &'static [Param],
+    overrides: &[(impl AsRef<str>, i64)],
+    f: impl FnOnce() -> T,
+) -> T {
+    for (name, _) in overrides {
+        let name = name.as_ref();
+        assert!(
+            params.iter().any(|&(param, _)| param == name),
+            "unknown parameter: {name}"
+        );
+    }
+    let values = params
+        .iter()
+        .map(|&(param, default)| {
+            let value = overrides
+                .iter()
+                .find(|(name, _)| name.as_ref() == param)
+                .map_or(default, |&(_, value)| value);
+            (param, value)
+        })
+        .collect();
+    let previous = VALUES.with(|current| current.replace(values));
+    let result = f();
+    VALUES.with(|current| current.replace(previous));
+    result
+}
+
+/// Returns the value of a parameter of the running day. Panics when the day
+/// has no such parameter, or when the value doesn't fit in `T`.
+pub fn param<T: TryFrom<i64>>(name: &str) -> T {
+    let value = VALUES.with(|values| {
+        let values = values.borrow();
+        values
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|&(_, value)| value)
+    });
+    let value = value.unwrap_or_else(|| panic!("unknown parameter: {name}"));
+    T::try_from(value).unwrap_or_else(|_| panic!("parameter {name} is out of range: {value}"))
+}
+
+/// Describes the parameters and their defaults, e.g. `steps=10 size=5`.
+pub fn describe(params: &[Param]) -> String {
+    params
+        .iter()
+        .map(|(name, default)| format!("{name}={default}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides() {
+        const PARAMS: &[Param] = &[("steps", 10), ("size", 5)];
+        let values = || (param::<usize>("steps"), param::<u8>("size"));
+        assert_eq!((10, 5), with(PARAMS, &[] as &[(&str, i64)], values));
+        assert_eq!((1000, 5), with(PARAMS, &[("steps", 1000)], values));
+        assert_eq!("steps=10 size=5", describe(PARAMS));
+        let result = std::panic::catch_unwind(|| with(PARAMS, &[("size", -1)], values));
+        assert!(result.is_err());
+    }
+}
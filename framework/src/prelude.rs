@@ -1,5 +1,8 @@
 pub use crate::day::{Day, DayCommon};
+pub use crate::normalize::Normalization;
+pub use crate::params::param;
 pub use crate::parsers::{self, ParseResult};
+pub use crate::rng::Rng;
 pub use crate::submissions::*;
 pub use crate::utils::*;
 pub use crate::vec::{Vec2, Vec2i, Vec2u};
@@ -0,0 +1,97 @@
+//! Renders answers: with colors for the terminal, as plain text to submit and
+//! compare, or as a JSON value.
+
+use crate::day::{Answer, Term};
+use colored::{Color, Colorize};
+use std::fmt::Write;
+
+const SYMBOL_COLOR: Color = Color::BrightYellow;
+
+/// Renders an answer with colors, including how it was derived.
+pub fn terminal(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(x) => x.to_string().white().bold().to_string(),
+        Answer::Text(text) | Answer::Block(text) => text.white().bold().to_string(),
+        Answer::Derived { terms, result } => {
+            let mut str = String::new();
+            for term in terms {
+                match term {
+                    Term::Value(value) => write!(str, "{value} ").unwrap(),
+                    Term::Symbol(symbol) => write!(str, "{} ", symbol.color(SYMBOL_COLOR)).unwrap(),
+                }
+            }
+            str + &terminal(result)
+        }
+    }
+}
+
+/// Renders the bare answer, as it is submitted and stored as known answer.
+pub fn plain(answer: &Answer) -> String {
+    match answer.result() {
+        Answer::Int(x) => x.to_string(),
+        Answer::Text(text) | Answer::Block(text) => text.clone(),
+        Answer::Derived { .. } => unreachable!(),
+    }
+}
+
+/// Renders the bare answer as a JSON value, a number for integers.
+pub fn json(answer: &Answer) -> String {
+    match answer.result() {
+        Answer::Int(x) => x.to_string(),
+        answer => {
+            let mut str = String::new();
+            json_string_into(&mut str, &plain(answer));
+            str
+        }
+    }
+}
+
+pub(crate) fn json_string_into(out: &mut String, str: &str) {
+    out.push('"');
+    for c in str.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// The amount of columns text takes up in a terminal, skipping the escape
+/// codes that set colors.
+pub fn visible_width(str: &str) -> usize {
+    let mut width = 0;
+    let mut chars = str.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Control sequences end in a byte in the range `@` to `~`.
+            if chars.next() == Some('[') {
+                chars.find(|c| ('@'..='~').contains(c));
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day::ToAnswer, submissions::MulSubmission};
+
+    #[test]
+    fn rendering() {
+        let derived = MulSubmission(3i64, -4).to_answer();
+        assert_eq!("-12", plain(&derived));
+        assert_eq!("-12", json(&derived));
+        assert_eq!("\"a\\nb\"", json(&"a\nb".to_answer()));
+        assert_eq!(Answer::Block("a\nb".to_owned()), "a\nb".to_answer());
+        assert_eq!(7, visible_width("\x1b[93m×\x1b[0m \x1b[1;37m-12\x1b[0m ✓"));
+    }
+}
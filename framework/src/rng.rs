@@ -0,0 +1,56 @@
+use std::ops::Range;
+
+/// A small deterministic random number generator (SplitMix64), used to
+/// generate inputs. The same seed always gives the same numbers, on every
+/// platform.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range, which must not be empty.
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(range.start < range.end, "empty range");
+        let len = range.end.abs_diff(range.start);
+        // The modulo bias is negligible for the small ranges used by inputs.
+        range.start.wrapping_add((self.next_u64() % len) as i64)
+    }
+
+    /// Returns a number below `n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..n as i64) as usize
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn determinism() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..100).map(|_| rng.range(-5..5)).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(1), numbers(1));
+        assert_ne!(numbers(1), numbers(2));
+        assert!(numbers(3).iter().all(|n| (-5..5).contains(n)));
+        assert_eq!(0xE220_A839_7B1D_CDAF, Rng::new(0).next_u64());
+    }
+}
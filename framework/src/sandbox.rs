@@ -0,0 +1,175 @@
+use crate::allocator::{self, format_bytes};
+use std::{
+    alloc::Layout,
+    any::Any,
+    cell::{Cell, RefCell},
+    io::Write,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Once},
+    thread,
+    time::Duration,
+};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// Maximum amount of bytes allocated at once.
+    pub memory: Option<usize>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    #[error("panicked: {0}")]
+    Panicked(String),
+    #[error("timed out after {0:.2?}")]
+    TimedOut(Duration),
+    #[error("exceeded the memory limit of {}", format_bytes(*.0))]
+    OutOfMemory(usize),
+}
+
+impl Failure {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Failure::Panicked(_) => "panicked",
+            Failure::TimedOut(_) => "timed_out",
+            Failure::OutOfMemory(_) => "out_of_memory",
+        }
+    }
+}
+
+thread_local! {
+    static IS_SANDBOXED: Cell<bool> = const { Cell::new(false) };
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Panic payload used when a sandboxed thread exceeds its memory limit.
+struct MemoryLimitExceeded;
+
+/// Installs hooks which silence panics in sandboxed threads, and turn failed
+/// allocations into panics when they exceed the memory limit. Outside of a
+/// sandbox, the default behavior is kept.
+fn install_hooks() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_panic_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IS_SANDBOXED.with(Cell::get) {
+                return default_panic_hook(info);
+            }
+            if info.payload().is::<MemoryLimitExceeded>() {
+                return;
+            }
+            let mut message = payload_to_string(info.payload());
+            if let Some(location) = info.location() {
+                message = format!("{message} at {location}");
+            }
+            PANIC_MESSAGE.with(|x| *x.borrow_mut() = Some(message));
+        }));
+
+        std::alloc::set_alloc_error_hook(|layout: Layout| {
+            if IS_SANDBOXED.with(Cell::get) && allocator::thread_limit_exceeded() {
+                panic::panic_any(MemoryLimitExceeded);
+            }
+            // Same as the default hook, after which the process is aborted.
+            let _ = writeln!(
+                std::io::stderr(),
+                "memory allocation of {} bytes failed",
+                layout.size()
+            );
+        });
+    });
+}
+
+fn payload_to_string(payload: &(dyn Any + Send)) -> String {
+    if let Some(str) = payload.downcast_ref::<&str>() {
+        str.to_string()
+    } else if let Some(str) = payload.downcast_ref::<String>() {
+        str.clone()
+    } else {
+        "unknown panic payload".to_owned()
+    }
+}
+
+/// Executes `f` on a separate thread, capturing any panic, and enforcing the
+/// limits. When the timeout expires, the thread is abandoned and keeps
+/// running in the background, since threads cannot be cancelled.
+pub fn run<T, F>(name: String, limits: &Limits, f: F) -> Result<T, Failure>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    install_hooks();
+    let (tx, rx) = mpsc::channel();
+    let memory_limit = limits.memory;
+    let spawned = thread::Builder::new()
+        .name(name)
+        // Match the usual size of the main thread, for deeply recursive days.
+        .stack_size(8 << 20)
+        .spawn(move || {
+            IS_SANDBOXED.with(|x| x.set(true));
+            allocator::set_thread_limit(memory_limit);
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            allocator::set_thread_limit(None);
+            let _ = tx.send(result.map_err(|payload| {
+                if payload.is::<MemoryLimitExceeded>() {
+                    return Failure::OutOfMemory(memory_limit.unwrap_or(usize::MAX));
+                }
+                let message = PANIC_MESSAGE.with(|x| x.borrow_mut().take());
+                Failure::Panicked(message.unwrap_or_else(|| payload_to_string(&*payload)))
+            }));
+        });
+    if let Err(e) = spawned {
+        return Err(Failure::Panicked(format!("failed to spawn thread: {e}")));
+    }
+
+    match limits.timeout {
+        Some(timeout) => rx
+            .recv_timeout(timeout)
+            .unwrap_or(Err(Failure::TimedOut(timeout))),
+        None => rx
+            .recv()
+            .unwrap_or_else(|_| Err(Failure::Panicked("thread exited unexpectedly".to_owned()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::TrackingAllocator;
+
+    #[global_allocator]
+    static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+    #[test]
+    fn failures() {
+        let limits = Limits::default();
+        assert_eq!(Ok(5), run("ok".to_owned(), &limits, || 5));
+        match run("panic".to_owned(), &limits, || -> u32 {
+            panic!("oh no {}", 5)
+        }) {
+            Err(Failure::Panicked(message)) => assert!(message.starts_with("oh no 5 at ")),
+            x => panic!("unexpected result: {x:?}"),
+        }
+
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(10)),
+            memory: None,
+        };
+        let result = run("timeout".to_owned(), &limits, || {
+            thread::sleep(Duration::from_millis(500))
+        });
+        assert_eq!(Err(Failure::TimedOut(Duration::from_millis(10))), result);
+
+        let limits = Limits {
+            timeout: None,
+            memory: Some(1 << 20),
+        };
+        assert_eq!(
+            Ok(1000),
+            run("small".to_owned(), &limits, || vec![0u8; 1000].len())
+        );
+        let result = run("large".to_owned(), &limits, || vec![0u8; 2 << 20].len());
+        assert_eq!(Err(Failure::OutOfMemory(1 << 20)), result);
+    }
+}
@@ -1,25 +1,24 @@
-use crate::day::{AutoImplementToColoredString, ColoredOutput, ToColoredString};
-use colored::{Color, Colorize};
+use crate::day::{Answer, AutoImplementToAnswer, Term, ToAnswer};
 use std::{
     fmt::Display,
     ops::{Add, Mul, Sub},
 };
 
-const SYMBOL_COLOR: Color = Color::BrightYellow;
-
 macro_rules! impl_submission {
     ($name:ident, $op_trait:ident, $op_fn:ident, $op_str:literal) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq)]
         pub struct $name<T: Clone + Display + $op_trait<Output = T>>(pub T, pub T);
-        impl<T: Clone + Display + $op_trait<Output = T>> ToColoredString for $name<T> {
-            fn to_colored(self) -> ColoredOutput {
+        impl<T: Clone + Display + ToAnswer + $op_trait<Output = T>> ToAnswer for $name<T> {
+            fn to_answer(self) -> Answer {
                 let result = self.0.clone().$op_fn(self.1.clone());
-                let result = result.to_string().bold().white();
-                let op = $op_str.color(SYMBOL_COLOR);
-                let eq = "=".color(SYMBOL_COLOR);
-                ColoredOutput {
-                    str: format!("{} {} {} {} {}", self.0, op, self.1, eq, result),
-                    control_char_count: 29,
+                Answer::Derived {
+                    terms: vec![
+                        Term::Value(self.0.to_string()),
+                        Term::Symbol($op_str),
+                        Term::Value(self.1.to_string()),
+                        Term::Symbol("="),
+                    ],
+                    result: Box::new(result.to_answer()),
                 }
             }
         }
@@ -31,10 +30,7 @@ macro_rules! impl_submission {
             }
         }
 
-        impl<T: Clone + Display + $op_trait<Output = T>> !AutoImplementToColoredString
-            for $name<T>
-        {
-        }
+        impl<T: Clone + Display + $op_trait<Output = T>> !AutoImplementToAnswer for $name<T> {}
     };
 }
 
@@ -44,13 +40,11 @@ impl_submission!(MulSubmission, Mul, mul, "×");
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SubmissionContext<C: Display, T: Display>(pub C, pub T);
-impl<C: Display, T: Display> ToColoredString for SubmissionContext<C, T> {
-    fn to_colored(self) -> ColoredOutput {
-        let result = self.1.to_string().bold().white();
-        let op = "=>".color(SYMBOL_COLOR);
-        ColoredOutput {
-            str: format!("{} {} {}", self.0, op, result),
-            control_char_count: 20,
+impl<C: Display, T: Display + ToAnswer> ToAnswer for SubmissionContext<C, T> {
+    fn to_answer(self) -> Answer {
+        Answer::Derived {
+            terms: vec![Term::Value(self.0.to_string()), Term::Symbol("=>")],
+            result: Box::new(self.1.to_answer()),
         }
     }
 }
@@ -59,4 +53,4 @@ impl<C: Display, T: Display> Display for SubmissionContext<C, T> {
         write!(f, "{} => {}", self.0, self.1)
     }
 }
-impl<C: Display, T: Display> !AutoImplementToColoredString for SubmissionContext<C, T> {}
+impl<C: Display, T: Display> !AutoImplementToAnswer for SubmissionContext<C, T> {}
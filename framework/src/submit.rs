@@ -0,0 +1,579 @@
+use crate::{
+    cli::Options,
+    day::{Day, DayResult, Part},
+    download, exec_day,
+    http::{url_encode, HttpClient, USER_AGENT},
+    inputs::Inputs,
+    normalize_input,
+    output::{self, RecordWriter},
+    print_day_header, render,
+};
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+/// The verdict of the website on a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Incorrect,
+    /// The answer was submitted too soon after the previous one, and was not
+    /// checked.
+    TooSoon,
+    /// The part was already solved, or is not unlocked yet.
+    WrongLevel,
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::TooSoon => "too soon",
+            Verdict::WrongLevel => "wrong level",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Verdict> {
+        use Verdict::*;
+        [Correct, TooHigh, TooLow, Incorrect, TooSoon, WrongLevel]
+            .into_iter()
+            .find(|verdict| verdict.name() == name)
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    /// Time until the next answer may be submitted.
+    pub cooldown: Duration,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SubmitError {
+    #[error("{0} was already submitted, and was {1}")]
+    AlreadySubmitted(String, &'static str),
+    #[error("already solved, the answer is {0}")]
+    AlreadySolved(String),
+    #[error("{0} is too high, {1} already was")]
+    KnownTooHigh(String, String),
+    #[error("{0} is too low, {1} already was")]
+    KnownTooLow(String, String),
+    #[error("the previous answer was submitted too recently, wait {}s", .0.as_secs())]
+    Cooldown(Duration),
+    #[error("invalid answer: {0:?}")]
+    InvalidAnswer(String),
+    #[error("unrecognized reply: {0}")]
+    UnknownReply(String),
+}
+
+/// Removes the tags from HTML, and collapses whitespace.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if in_tag => {}
+            c => text.push(c),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses durations like `4m 21s`, `one minute` or `5 minutes`.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let mut words = text.split_whitespace();
+    let mut seconds = 0;
+    while let Some(word) = words.next() {
+        let split = word
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(word.len());
+        let (amount, unit) = match word.split_at(split) {
+            ("", "one") => (1, words.next()?),
+            ("", _) => return None,
+            (amount, "") => (amount.parse().ok()?, words.next()?),
+            (amount, unit) => (amount.parse().ok()?, unit),
+        };
+        seconds += amount
+            * match unit.trim_end_matches('s') {
+                "h" | "hour" => 3600,
+                "m" | "minute" => 60,
+                "" | "second" => 1,
+                _ => return None,
+            };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// Parses the page the website replies with after submitting an answer.
+pub fn parse_reply(html: &str) -> Result<Reply, SubmitError> {
+    let text = strip_html(html);
+    let between = |start: &str, end: &str| {
+        let (_, rest) = text.split_once(start)?;
+        let (middle, _) = rest.split_once(end)?;
+        parse_wait(middle)
+    };
+    let (verdict, cooldown) = if text.contains("That's the right answer") {
+        (Verdict::Correct, None)
+    } else if text.contains("That's not the right answer") {
+        let verdict = if text.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Incorrect
+        };
+        (verdict, between("lease wait ", " before trying again"))
+    } else if text.contains("You gave an answer too recently") {
+        (Verdict::TooSoon, between("You have ", " left to wait"))
+    } else if text.contains("You don't seem to be solving the right level") {
+        (Verdict::WrongLevel, None)
+    } else {
+        let summary = text.chars().take(100).collect::<String>();
+        return Err(SubmitError::UnknownReply(summary));
+    };
+    Ok(Reply {
+        verdict,
+        cooldown: cooldown.unwrap_or_default(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Unix timestamp of the submission.
+    pub timestamp: u64,
+    pub day: u32,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub cooldown: Duration,
+}
+
+/// All submitted answers, stored as an append-only TSV file, used to avoid
+/// submitting known wrong answers and to respect the cooldown.
+#[derive(Debug, Clone)]
+pub struct SubmissionLog {
+    path: PathBuf,
+}
+
+const LOG_HEADER: &str = "timestamp\tday\tpart\tanswer\tverdict\tcooldown";
+
+fn part_nr(part: Part) -> u32 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+impl SubmissionLog {
+    pub fn new(path: PathBuf) -> SubmissionLog {
+        SubmissionLog { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<Attempt>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read {}", self.path.display()))
+            }
+        };
+        contents
+            .lines()
+            .filter(|line| !line.is_empty() && *line != LOG_HEADER)
+            .map(|line| Self::parse_line(line).ok_or_else(|| anyhow!("invalid line: {line}")))
+            .collect::<Result<_>>()
+            .with_context(|| format!("invalid submission log {}", self.path.display()))
+    }
+
+    fn parse_line(line: &str) -> Option<Attempt> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        if fields.len() != 6 {
+            return None;
+        }
+        Some(Attempt {
+            timestamp: fields[0].parse().ok()?,
+            day: fields[1].parse().ok()?,
+            part: match fields[2] {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return None,
+            },
+            answer: fields[3].to_owned(),
+            verdict: Verdict::parse(fields[4])?,
+            cooldown: Duration::from_secs(fields[5].parse().ok()?),
+        })
+    }
+
+    pub fn append(&self, attempt: &Attempt) -> Result<()> {
+        std::fs::create_dir_all(self.path.parent().unwrap())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("failed to open {}", self.path.display()))?;
+        let mut contents = String::new();
+        if file.metadata()?.len() == 0 {
+            contents.push_str(LOG_HEADER);
+            contents.push('\n');
+        }
+        writeln!(
+            contents,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            attempt.timestamp,
+            attempt.day,
+            part_nr(attempt.part),
+            attempt.answer,
+            attempt.verdict.name(),
+            attempt.cooldown.as_secs()
+        )
+        .unwrap();
+        file.write_all(contents.as_bytes())
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+/// Checks an answer against the previous attempts, before it is submitted.
+fn check(
+    attempts: &[Attempt],
+    now: u64,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<(), SubmitError> {
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(SubmitError::InvalidAnswer(answer.to_owned()));
+    }
+    let cooldown_end = attempts
+        .iter()
+        .map(|attempt| attempt.timestamp + attempt.cooldown.as_secs())
+        .max()
+        .unwrap_or(0);
+    let attempts = attempts
+        .iter()
+        .filter(|attempt| attempt.day == day && attempt.part == part);
+    let number = answer.parse::<i128>().ok();
+    for attempt in attempts {
+        let previous = &attempt.answer;
+        if attempt.verdict == Verdict::Correct {
+            return Err(SubmitError::AlreadySolved(previous.clone()));
+        }
+        if attempt.verdict.is_wrong() && previous == answer {
+            let verdict = attempt.verdict.name();
+            return Err(SubmitError::AlreadySubmitted(answer.to_owned(), verdict));
+        }
+        let bound = previous.parse::<i128>().ok();
+        match (attempt.verdict, number, bound) {
+            (Verdict::TooHigh, Some(number), Some(bound)) if number >= bound => {
+                return Err(SubmitError::KnownTooHigh(
+                    answer.to_owned(),
+                    previous.clone(),
+                ))
+            }
+            (Verdict::TooLow, Some(number), Some(bound)) if number <= bound => {
+                return Err(SubmitError::KnownTooLow(
+                    answer.to_owned(),
+                    previous.clone(),
+                ))
+            }
+            _ => {}
+        }
+    }
+    if now < cooldown_end {
+        return Err(SubmitError::Cooldown(Duration::from_secs(
+            cooldown_end - now,
+        )));
+    }
+    Ok(())
+}
+
+/// Submits answers, refusing answers that are known to be wrong, and answers
+/// that are submitted before the cooldown of the previous one ended.
+pub struct Submitter {
+    client: Box<dyn HttpClient>,
+    base_url: String,
+    year: u32,
+    log: SubmissionLog,
+}
+
+impl Submitter {
+    pub fn new(
+        client: Box<dyn HttpClient>,
+        base_url: &str,
+        year: u32,
+        log: SubmissionLog,
+    ) -> Submitter {
+        Submitter {
+            client,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            year,
+            log,
+        }
+    }
+
+    pub fn answer_url(&self, day: u32) -> String {
+        format!("{}/{}/day/{day}/answer", self.base_url, self.year)
+    }
+
+    /// Checks whether an answer may be submitted now, which `submit` also
+    /// does, returning the current time.
+    pub fn check(&self, day: u32, part: Part, answer: &str) -> Result<u64> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        check(&self.log.load()?, now, day, part, answer)?;
+        Ok(now)
+    }
+
+    /// Submits an answer, and records the attempt. Errors contain a
+    /// [`SubmitError`] when the answer was refused or the reply was not
+    /// understood, or a [`download::DownloadError`] when the request failed.
+    pub fn submit(&self, day: u32, part: Part, answer: &str, session_key: &str) -> Result<Reply> {
+        let now = self.check(day, part, answer)?;
+
+        let cookie = format!("session={session_key}");
+        let headers = [
+            ("Cookie", cookie.as_str()),
+            ("User-Agent", USER_AGENT),
+            ("Content-Type", "application/x-www-form-urlencoded"),
+        ];
+        let body = format!("level={}&answer={}", part_nr(part), url_encode(answer));
+        let response = self
+            .client
+            .post(&self.answer_url(day), &headers, body.as_bytes())
+            .map_err(|e| download::DownloadError::Transport(format!("{e:#}")))?;
+        let body = download::classify(response)?;
+        let reply = parse_reply(&String::from_utf8_lossy(&body))?;
+        self.log.append(&Attempt {
+            timestamp: now,
+            day,
+            part,
+            answer: answer.to_owned(),
+            verdict: reply.verdict,
+            cooldown: reply.cooldown,
+        })?;
+        Ok(reply)
+    }
+}
+
+/// Runs a part of a day against its default input, and submits the answer.
+pub(crate) fn submit_day(
+    inputs: &mut Inputs,
+    submitter: &Submitter,
+    options: &Options,
+    writer: &mut RecordWriter,
+    day: &'static dyn Day,
+) -> bool {
+    let day_nr = day.nr();
+    let part = options.part.unwrap();
+    let part_nr = if part == Part::One { 1 } else { 2 };
+    let (input, _) = normalize_input(day.normalization(), inputs.get(day_nr, None));
+    let answer = match exec_day(day, input, options).0 {
+        DayResult::Ran { pt1, pt2 } => {
+            let output = if part == Part::One { pt1 } else { pt2 };
+            output.unwrap().map(|answer| render::plain(&answer))
+        }
+        DayResult::NoInput(e) => Err(e.context("no input")),
+        DayResult::Rejected(e) => Err(e.context("invalid input")),
+        DayResult::ParseFailed(e) => Err(e.context("parse error")),
+        DayResult::Aborted(failure) => Err(anyhow::Error::from(failure).context("aborted")),
+    };
+    let result = answer
+        .as_ref()
+        .map_err(|e| format!("{e:#}"))
+        .and_then(|answer| {
+            let session_key = submitter
+                .check(day_nr, part, answer)
+                .and_then(|_| inputs.session_key())
+                .map_err(|e| format!("{e:#}"))?;
+            submitter
+                .submit(day_nr, part, answer, session_key)
+                .map_err(|e| format!("{e:#}"))
+        });
+    let answer = answer.as_deref().ok();
+    writer.write(&output::submit_record(day_nr, part_nr, answer, &result));
+    if writer.is_text() {
+        print_day_header(day_nr, None);
+        print!(" :: {}", format!("pt{part_nr}").bright_green());
+        if let Some(answer) = answer {
+            print!(" {}", answer.bold());
+        }
+        match &result {
+            Ok(reply) => {
+                let verdict = match reply.verdict {
+                    Verdict::Correct => reply.verdict.name().bright_green(),
+                    _ => reply.verdict.name().bright_red(),
+                };
+                print!(" :: {verdict}");
+                if !reply.cooldown.is_zero() {
+                    let wait = format!("wait {}s", reply.cooldown.as_secs());
+                    print!(" :: {}", wait.bright_yellow());
+                }
+            }
+            Err(e) => print!(" :: {}", e.bright_red()),
+        }
+        println!();
+    }
+    matches!(&result, Ok(reply) if reply.verdict == Verdict::Correct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{stub_server, UreqClient};
+
+    fn page(message: &str) -> String {
+        format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn replies() {
+        let reply =
+            |message: &str| parse_reply(&page(message)).map(|r| (r.verdict, r.cooldown.as_secs()));
+        assert_eq!(
+            Ok((Verdict::Correct, 0)),
+            reply("That's the right answer!  You are <em>one gold star</em> closer.")
+        );
+        assert_eq!(
+            Ok((Verdict::TooHigh, 60)),
+            reply("That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2021/about\">about page</a>.  Please wait one minute before trying again. <a href=\"/2021/day/1\">[Return to Day 1]</a>")
+        );
+        assert_eq!(
+            Ok((Verdict::TooLow, 300)),
+            reply("That's not the right answer; your answer is too low.  Please wait 5 minutes before trying again.")
+        );
+        assert_eq!(
+            Ok((Verdict::Incorrect, 0)),
+            reply("That's not the right answer.  If you're stuck, try again.")
+        );
+        assert_eq!(
+            Ok((Verdict::TooSoon, 261)),
+            reply("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 21s left to wait.")
+        );
+        assert_eq!(
+            Ok((Verdict::WrongLevel, 0)),
+            reply("You don't seem to be solving the right level.  Did you already complete it?")
+        );
+        assert!(matches!(
+            reply("Something else"),
+            Err(SubmitError::UnknownReply(_))
+        ));
+    }
+
+    #[test]
+    fn checks() {
+        let attempt = |part, answer: &str, verdict, cooldown| Attempt {
+            timestamp: 1000,
+            day: 3,
+            part,
+            answer: answer.to_owned(),
+            verdict,
+            cooldown: Duration::from_secs(cooldown),
+        };
+        let attempts = [
+            attempt(Part::One, "50", Verdict::TooHigh, 60),
+            attempt(Part::One, "20", Verdict::TooLow, 60),
+            attempt(Part::One, "ABC", Verdict::Incorrect, 60),
+            attempt(Part::Two, "123", Verdict::Correct, 0),
+        ];
+        let check = |now, answer: &str| check(&attempts, now, 3, Part::One, answer);
+        assert_eq!(Ok(()), check(1060, "30"));
+        assert_eq!(Ok(()), check(1060, "ABD"));
+        assert_eq!(
+            Err(SubmitError::Cooldown(Duration::from_secs(30))),
+            check(1030, "30")
+        );
+        assert_eq!(
+            Err(SubmitError::AlreadySubmitted("ABC".to_owned(), "incorrect")),
+            check(1060, "ABC")
+        );
+        assert_eq!(
+            Err(SubmitError::KnownTooHigh("51".to_owned(), "50".to_owned())),
+            check(1060, "51")
+        );
+        assert_eq!(
+            Err(SubmitError::KnownTooLow("15".to_owned(), "20".to_owned())),
+            check(1060, "15")
+        );
+        assert_eq!(
+            Err(SubmitError::AlreadySubmitted("20".to_owned(), "too low")),
+            check(1060, "20")
+        );
+        assert!(matches!(
+            check(1060, ""),
+            Err(SubmitError::InvalidAnswer(_))
+        ));
+        assert_eq!(
+            Err(SubmitError::AlreadySolved("123".to_owned())),
+            super::check(&attempts, 1060, 3, Part::Two, "124")
+        );
+        assert_eq!(Ok(()), super::check(&attempts, 1060, 4, Part::Two, "123"));
+    }
+
+    #[test]
+    fn submission() {
+        let path = std::env::temp_dir().join(format!("aoc-{}-submissions.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let (base_url, server) = stub_server::serve(vec![
+            (
+                "200 OK",
+                page("That's not the right answer; your answer is too low."),
+            ),
+            ("200 OK", page("That's the right answer!")),
+        ]);
+        let log = SubmissionLog::new(path.clone());
+        let submitter = Submitter::new(Box::<UreqClient>::default(), &base_url, 2015, log.clone());
+
+        let reply = submitter.submit(7, Part::Two, "10", "secret").unwrap();
+        assert_eq!(Verdict::TooLow, reply.verdict);
+        let error = submitter.submit(7, Part::Two, "10", "secret").unwrap_err();
+        assert!(matches!(
+            error.downcast::<SubmitError>(),
+            Ok(SubmitError::AlreadySubmitted(..))
+        ));
+        let error = submitter.submit(7, Part::Two, "-5", "secret").unwrap_err();
+        assert!(matches!(
+            error.downcast::<SubmitError>(),
+            Ok(SubmitError::KnownTooLow(..))
+        ));
+        let reply = submitter.submit(7, Part::Two, "99", "secret").unwrap();
+        assert_eq!(Verdict::Correct, reply.verdict);
+
+        let requests = server.join().unwrap();
+        assert_eq!(2, requests.len());
+        assert!(requests[0]
+            .head
+            .starts_with("post /2015/day/7/answer http/1.1\r\n"));
+        assert!(requests[0].head.contains("cookie: session=secret\r\n"));
+        assert_eq!("level=2&answer=10", requests[0].body);
+        assert_eq!("level=2&answer=99", requests[1].body);
+        let attempts = log.load().unwrap();
+        assert_eq!(
+            vec![(Verdict::TooLow, "10"), (Verdict::Correct, "99")],
+            attempts
+                .iter()
+                .map(|attempt| (attempt.verdict, attempt.answer.as_str()))
+                .collect::<Vec<_>>()
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    answers::Verification,
+    day::{Answer, DayResult},
+    print_day_header, render, verification_marker,
+};
+use anyhow::Result;
+use colored::Colorize;
+use std::{
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The answers and timing of a single run of a day, to compare later runs
+/// against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    /// The error that prevented the parts from running, if any.
+    pub error: Option<String>,
+    pub parts: [Option<Result<String, String>>; 2],
+    pub duration: Duration,
+}
+
+impl RunSummary {
+    pub fn new(result: &DayResult, duration: Duration) -> RunSummary {
+        fn part(pt: &Option<Result<Answer>>) -> Option<Result<String, String>> {
+            pt.as_ref().map(|pt| match pt {
+                Ok(answer) => Ok(render::plain(answer)),
+                Err(e) => Err(e.to_string()),
+            })
+        }
+        let (error, parts) = match result {
+            DayResult::NoInput(e) => (Some(format!("no input: {e:#}")), [None, None]),
+            DayResult::Rejected(e) => (Some(format!("invalid input: {e:#}")), [None, None]),
+            DayResult::ParseFailed(e) => (Some(format!("parse error: {e}")), [None, None]),
+            DayResult::Aborted(failure) => (Some(failure.to_string()), [None, None]),
+            DayResult::Ran { pt1, pt2 } => (None, [part(pt1), part(pt2)]),
+        };
+        RunSummary {
+            error,
+            parts,
+            duration,
+        }
+    }
+}
+
+type FileState = Option<(SystemTime, u64)>;
+
+fn file_state(path: &Path) -> FileState {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Polls each set of files for changes, and calls `on_change` with the
+/// indices of the sets that changed, together with the changed files. A
+/// change is only reported once the files stop changing, to skip partial
+/// writes.
+pub fn watch(files: Vec<Vec<PathBuf>>, mut on_change: impl FnMut(&[usize], &[PathBuf])) -> ! {
+    let snapshot = || -> Vec<Vec<FileState>> {
+        files
+            .iter()
+            .map(|paths| paths.iter().map(|path| file_state(path)).collect())
+            .collect()
+    };
+    let mut previous = snapshot();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let mut current = snapshot();
+        if current == previous {
+            continue;
+        }
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let next = snapshot();
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+
+        let mut changed_sets = Vec::new();
+        let mut changed_paths = Vec::new();
+        let sets = files.iter().zip(&previous).zip(&current).enumerate();
+        for (index, ((paths, previous), current)) in sets {
+            let mut is_changed = false;
+            for ((path, previous), current) in paths.iter().zip(previous).zip(current) {
+                if previous != current {
+                    is_changed = true;
+                    if !changed_paths.contains(path) {
+                        changed_paths.push(path.clone());
+                    }
+                }
+            }
+            if is_changed {
+                changed_sets.push(index);
+            }
+        }
+        previous = current;
+        on_change(&changed_sets, &changed_paths);
+    }
+}
+
+pub fn print_changes(paths: &[PathBuf]) {
+    let paths = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    println!("\n{} {}", "changed:".bright_blue().bold(), paths.join(", "));
+}
+
+/// Prints a compact, single line comparison of a run against the previous run.
+pub fn print_diff(
+    day_nr: u32,
+    variant: Option<&str>,
+    previous: &RunSummary,
+    current: &RunSummary,
+    verifications: &[Option<Verification>; 2],
+) {
+    print_day_header(day_nr, variant);
+    if let Some(error) = &current.error {
+        println!(" :: {}", error.bright_red());
+        return;
+    }
+
+    for (i, (label, verification)) in ["pt1", "pt2"].into_iter().zip(verifications).enumerate() {
+        let value = match &current.parts[i] {
+            Some(value) => value,
+            None => continue,
+        };
+        let str = |value: &Result<String, String>| match value {
+            Ok(answer) => answer.replace('\n', "⏎"),
+            Err(e) => format!("error: {e}"),
+        };
+        print!(" :: {} ", label.bright_green());
+        match &previous.parts[i] {
+            Some(previous) if previous == value => print!("{}", str(value).bright_black()),
+            Some(previous) => print!(
+                "{} → {}",
+                str(previous).bright_black(),
+                str(value).bright_yellow().bold()
+            ),
+            None => print!("{}", str(value).bright_yellow().bold()),
+        }
+        if let Some(verification) = verification {
+            print!(" {}", verification_marker(verification));
+        }
+    }
+
+    let (before, after) = (previous.duration, current.duration);
+    print!(
+        " :: {before:.2?} → {}",
+        format!("{after:.2?}").white().bold()
+    );
+    if !before.is_zero() {
+        let change = (after.as_nanos() as f64 / before.as_nanos() as f64 - 1.0) * 100.0;
+        print!(" ({change:+.1}%)");
+    }
+    println!();
+}
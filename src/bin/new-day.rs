@@ -0,0 +1,101 @@
+//! Creates `src/dayXX.rs` for a new day from a template, which is picked up by
+//! the build script without any further changes:
+//! `cargo run --bin new-day -- XX`.
+
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const TEMPLATE: &str = r#"use crate::prelude::*;
+
+day!({day}, parse => part1, part2);
+
+pub fn part1(input: &[u32]) -> usize {
+    input.len()
+}
+
+pub fn part2(input: &[u32]) -> usize {
+    input.len()
+}
+
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<u32>> {
+    use parsers::*;
+    number::<u32>().sep_by(token(b'\n')).parse(input)
+}
+
+tests! {
+    const EXAMPLE: &'static [u8] = b"\
+1
+2
+3";
+
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 3);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 3);
+}
+"#;
+
+fn parse_day(day: &str) -> Result<u32> {
+    match day.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(anyhow!("invalid day: {day}, expected 1-25")),
+    }
+}
+
+fn create(root: &Path, day: u32) -> Result<PathBuf> {
+    let path = root.join("src").join(format!("day{day:02}.rs"));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    file.write_all(TEMPLATE.replace("{day}", &day.to_string()).as_bytes())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.as_slice() {
+        [day] => parse_day(day).and_then(|day| {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+            create(root, day).map(|path| (day, path))
+        }),
+        _ => Err(anyhow!("usage: new-day <DAY>")),
+    };
+    match result {
+        Ok((day, path)) => {
+            println!("Created {}", path.display().to_string().bright_green());
+            println!("Use `cargo run --release -- examples {day} --write` to add the examples.");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{} {e:#}", "error:".bright_red().bold());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creation() {
+        assert_eq!(7, parse_day("07").unwrap());
+        assert!(parse_day("26").is_err());
+
+        let root = std::env::temp_dir().join(format!("new_day_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let path = create(&root, 7).unwrap();
+        assert_eq!(root.join("src/day07.rs"), path);
+        let source = std::fs::read_to_string(&path).unwrap();
+        assert!(source.contains("day!(7, parse => part1, part2);"));
+        assert!(create(&root, 7).is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
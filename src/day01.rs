@@ -1,8 +1,8 @@
 use crate::prelude::*;
 
-day!(1, parse => pt1, pt2);
+day!(1, parse => part1, part2);
 
-fn pt1(input: &[u32]) -> usize {
+pub fn part1(input: &[u32]) -> usize {
     input
         .iter()
         .tuple_windows()
@@ -10,7 +10,7 @@ fn pt1(input: &[u32]) -> usize {
         .count()
 }
 
-fn pt2(input: &[u32]) -> usize {
+pub fn part2(input: &[u32]) -> usize {
     input
         .iter()
         .tuple_windows()
@@ -20,7 +20,7 @@ fn pt2(input: &[u32]) -> usize {
         .count()
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<u32>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<u32>> {
     use parsers::*;
     number::<u32>().sep_by(token(b'\n')).parse(input)
 }
@@ -38,6 +38,6 @@ tests! {
 260
 263";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 7);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 5);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 7);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 5);
 }
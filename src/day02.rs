@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-day!(2, parse => pt1, pt2);
+day!(2, parse => part1, part2);
 
 #[derive(Debug, Copy, Clone)]
 pub enum Direction {
@@ -9,9 +9,9 @@ pub enum Direction {
     Up,
 }
 
-type Instruction = (Direction, u32);
+pub type Instruction = (Direction, u32);
 
-fn pt1(input: &[Instruction]) -> MulSubmission<i32> {
+pub fn part1(input: &[Instruction]) -> MulSubmission<i32> {
     let mut hpos = 0;
     let mut depth = 0;
     for &(direction, amount) in input {
@@ -25,7 +25,7 @@ fn pt1(input: &[Instruction]) -> MulSubmission<i32> {
     MulSubmission(hpos, depth)
 }
 
-fn pt2(input: &[Instruction]) -> MulSubmission<i32> {
+pub fn part2(input: &[Instruction]) -> MulSubmission<i32> {
     let mut aim = 0;
     let mut hpos = 0;
     let mut depth = 0;
@@ -43,7 +43,7 @@ fn pt2(input: &[Instruction]) -> MulSubmission<i32> {
     MulSubmission(hpos, depth)
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Instruction>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Instruction>> {
     use parsers::*;
     let direction = token((b"forward ", Direction::Forward))
         .or(token((b"down ", Direction::Down)))
@@ -61,6 +61,6 @@ up 3
 down 8
 forward 2";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => MulSubmission(15, 10));
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => MulSubmission(15, 60));
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => MulSubmission(15, 10));
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => MulSubmission(15, 60));
 }
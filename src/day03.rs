@@ -1,12 +1,13 @@
 use crate::prelude::*;
 
-day!(3, parse => pt1::<12>, pt2::<12>);
+day!(3, parse => pt1, pt2, validator = validate, params = &[("bits", 12)]);
 
-fn pt1<const BIT_COUNT: usize>(input: &[usize]) -> MulSubmission<usize> {
-    let mut bit_counts = [0; BIT_COUNT];
+/// `bit_count` is the width of the numbers, 12 in the puzzle.
+pub fn part1(input: &[usize], bit_count: usize) -> MulSubmission<usize> {
+    let mut bit_counts = vec![0; bit_count];
 
     for &nr in input {
-        for bit in 0..BIT_COUNT {
+        for bit in 0..bit_count {
             bit_counts[bit] += (nr >> bit) & 1;
         }
     }
@@ -20,37 +21,44 @@ fn pt1<const BIT_COUNT: usize>(input: &[usize]) -> MulSubmission<usize> {
             gamma | (if bit { 1 } else { 0 } << index)
         });
 
-    let epsilon = gamma ^ ((1 << BIT_COUNT) - 1);
+    let epsilon = gamma ^ ((1 << bit_count) - 1);
     MulSubmission(gamma, epsilon)
 }
 
-fn pt2<const BIT_COUNT: usize>(input: &[usize]) -> MulSubmission<usize> {
-    let mut temp = input.to_vec();
-    let oxygen_generator_rating =
-        pt2_compute_rating::<BIT_COUNT>(Rating::OxygenGenerator, &mut temp);
-    temp.clear();
-    temp.extend_from_slice(input);
-    let co2_scrubber_rating = pt2_compute_rating::<BIT_COUNT>(Rating::CO2Scrubber, &mut temp);
+pub fn part2(mut input: Vec<usize>, bit_count: usize) -> MulSubmission<usize> {
+    // Once sorted, the numbers that share a prefix are next to each other, with
+    // the ones continuing with a 0 first, so each rating narrows down a range.
+    input.sort_unstable();
+    let oxygen_generator_rating = pt2_compute_rating(Rating::OxygenGenerator, bit_count, &input);
+    let co2_scrubber_rating = pt2_compute_rating(Rating::CO2Scrubber, bit_count, &input);
 
     MulSubmission(oxygen_generator_rating, co2_scrubber_rating)
 }
 
+fn pt1(input: &[usize]) -> MulSubmission<usize> {
+    part1(input, param("bits"))
+}
+
+fn pt2(input: Vec<usize>) -> MulSubmission<usize> {
+    part2(input, param("bits"))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Rating {
     OxygenGenerator,
     CO2Scrubber,
 }
 
-fn pt2_compute_rating<const BIT_COUNT: usize>(rating: Rating, input: &mut Vec<usize>) -> usize {
-    for bit in (0..BIT_COUNT).rev() {
-        let mut ones_count = 0;
-        for &nr in input.iter() {
-            ones_count += (nr >> bit) & 1;
-        }
-        let zeroes_count = input.len() - ones_count;
-        let kept_if = (ones_count >= zeroes_count) == (rating == Rating::OxygenGenerator);
-        let kept_if = if kept_if { 1 } else { 0 };
-        input.retain(|&nr| (nr >> bit) & 1 == kept_if);
+fn pt2_compute_rating(rating: Rating, bit_count: usize, mut input: &[usize]) -> usize {
+    for bit in (0..bit_count).rev() {
+        let zeroes_count = input.partition_point(|&nr| (nr >> bit) & 1 == 0);
+        let ones_count = input.len() - zeroes_count;
+        let keep_ones = (ones_count >= zeroes_count) == (rating == Rating::OxygenGenerator);
+        input = if keep_ones {
+            &input[zeroes_count..]
+        } else {
+            &input[..zeroes_count]
+        };
         if input.len() == 1 {
             break;
         }
@@ -59,7 +67,22 @@ fn pt2_compute_rating<const BIT_COUNT: usize>(rating: Rating, input: &mut Vec<us
     input[0]
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<usize>> {
+/// The parts assume numbers of `bits` bits, while the parser would happily
+/// accept shorter or longer lines.
+fn validate(input: &[u8]) -> Result<()> {
+    let bit_count = param::<usize>("bits");
+    let lines = input.split(|&c| c == b'\n').filter(|line| !line.is_empty());
+    match lines.enumerate().find(|(_, line)| line.len() != bit_count) {
+        Some((i, line)) => Err(anyhow!(
+            "line {} has {} bits, expected {bit_count}",
+            i + 1,
+            line.len()
+        )),
+        None => Ok(()),
+    }
+}
+
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<usize>> {
     use parsers::*;
     let bit = token((b'1', 1)).or(token((b'0', 0)));
     let word = bit.fold(0, |nr, bit| (nr << 1) ^ bit);
@@ -81,6 +104,6 @@ tests! {
 00010
 01010";
 
-    simple_tests!(parse, pt1::<5>, pt1_tests, EXAMPLE => MulSubmission(22, 9));
-    simple_tests!(parse, pt2::<5>, pt2_tests, EXAMPLE => MulSubmission(23, 10));
+    simple_tests!(parse, pt1, pt1_tests, params(bits = 5), EXAMPLE => MulSubmission(22, 9));
+    simple_tests!(parse, pt2, pt2_tests, params(bits = 5), EXAMPLE => MulSubmission(23, 10));
 }
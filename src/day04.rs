@@ -1,16 +1,16 @@
 use crate::prelude::*;
 use ahash::AHashSet;
 
-day!(4, parse => pt1, pt2);
+day!(4, parse => part1, part2);
 
-const WIDTH: usize = 5;
-const HEIGHT: usize = 5;
-type BingoBoard = Box<[[u32; HEIGHT]; WIDTH]>;
+pub const WIDTH: usize = 5;
+pub const HEIGHT: usize = 5;
+pub type BingoBoard = Box<[[u32; HEIGHT]; WIDTH]>;
 
 #[derive(Debug, Clone)]
-struct Input {
-    numbers: Vec<u32>,
-    bingo_boards: Vec<BingoBoard>,
+pub struct Input {
+    pub numbers: Vec<u32>,
+    pub bingo_boards: Vec<BingoBoard>,
 }
 
 fn is_bingo(board: &BingoBoard, seen_digits: &AHashSet<u32>) -> bool {
@@ -39,7 +39,7 @@ fn get_unmarked_sum(board: &BingoBoard, seen_digits: &AHashSet<u32>) -> u32 {
         .sum()
 }
 
-fn pt1(input: &Input) -> Result<MulSubmission<u32>> {
+pub fn part1(input: &Input) -> Result<MulSubmission<u32>> {
     let mut seen_digits = AHashSet::with_capacity(input.numbers.len());
     for &number in &input.numbers {
         seen_digits.insert(number);
@@ -60,7 +60,7 @@ fn pt1(input: &Input) -> Result<MulSubmission<u32>> {
     Err(anyhow!("no bingo"))
 }
 
-fn pt2(input: &Input) -> Result<MulSubmission<u32>> {
+pub fn part2(input: &Input) -> Result<MulSubmission<u32>> {
     let mut remaining_boards = input.bingo_boards.clone();
 
     let mut seen_digits = AHashSet::with_capacity(input.numbers.len());
@@ -83,7 +83,7 @@ fn pt2(input: &Input) -> Result<MulSubmission<u32>> {
     ))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Input> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Input> {
     use parsers::{special::grid, *};
     let numbers = number::<u32>().sep_by(token(b',')).trailed(token(b"\n\n"));
     let bingo_digit = token(b' ').repeat().opt().then(number::<u32>());
@@ -120,6 +120,6 @@ tests! {
 22 11 13  6  5
  2  0 12  3  7";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => MulSubmission(188, 24));
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => MulSubmission(148, 13));
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => MulSubmission(188, 24));
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => MulSubmission(148, 13));
 }
@@ -1,15 +1,16 @@
 use crate::prelude::*;
+use std::fmt::Write;
 
-day!(5, parse => pt1, pt2);
+day!(5, parse => part1, part2, generator = generate);
 
 #[derive(Debug, Clone, Copy)]
-struct Line {
-    from: Vec2i,
-    to: Vec2i,
+pub struct Line {
+    pub from: Vec2i,
+    pub to: Vec2i,
 }
 
 #[derive(Debug, Clone)]
-struct LineIter {
+pub struct LineIter {
     current: Vec2i,
     offset: Vec2i,
     remainder: usize,
@@ -74,7 +75,7 @@ fn count_overlapping_points<'i, I: Iterator<Item = &'i Line> + Clone + 'i>(lines
     board.iter().filter(|&&count| count >= 2).count()
 }
 
-fn pt1(input: &[Line]) -> usize {
+pub fn part1(input: &[Line]) -> usize {
     count_overlapping_points(
         input
             .iter()
@@ -82,11 +83,11 @@ fn pt1(input: &[Line]) -> usize {
     )
 }
 
-fn pt2(input: &[Line]) -> usize {
+pub fn part2(input: &[Line]) -> usize {
     count_overlapping_points(input.iter())
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Line>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Line>> {
     use parsers::*;
     let coord = number::<usize>().trailed(token(b',')).and(number::<usize>());
     let coord = coord.map(|(x, y)| Vec2i::from((x as isize, y as isize)));
@@ -95,6 +96,33 @@ fn parse(input: &[u8]) -> ParseResult<Vec<Line>> {
     line.sep_by(token(b'\n')).parse(input)
 }
 
+/// Generates horizontal, vertical and diagonal lines, the real input has 500
+/// lines in a 1000x1000 area. The area grows with the amount of lines, so
+/// they overlap about as often at every scale.
+fn generate(seed: u64, scale: u32) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let size = (1000.0 * (scale as f64).sqrt()).round() as i64;
+    let mut input = String::new();
+    for _ in 0..500 * scale {
+        let (x, y) = (rng.range(0..size), rng.range(0..size));
+        let (dx, dy, max_len) = loop {
+            let (dx, dy) = (rng.range(-1..2), rng.range(-1..2));
+            let room = |pos: i64, d: i64| match d {
+                -1 => pos,
+                1 => size - 1 - pos,
+                _ => i64::MAX,
+            };
+            let max_len = room(x, dx).min(room(y, dy));
+            if (dx, dy) != (0, 0) && max_len > 0 {
+                break (dx, dy, max_len);
+            }
+        };
+        let len = rng.range(1..max_len.min(size / 2) + 1);
+        writeln!(input, "{x},{y} -> {},{}", x + dx * len, y + dy * len).unwrap();
+    }
+    input.into_bytes()
+}
+
 tests! {
     const EXAMPLE: &'static [u8] = b"\
 0,9 -> 5,9
@@ -108,6 +136,7 @@ tests! {
 0,0 -> 8,8
 5,5 -> 8,2";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 5);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 12);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 5);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 12);
+    generator_tests!(generate, parse);
 }
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-day!(6, parse => pt1, pt2);
+day!(6, parse => pt1, pt2, params = &[("pt1_days", 80), ("pt2_days", 256)]);
 
 fn pts(input: &[u64], days: usize) -> u64 {
     const CYCLE_LENGTH: usize = 7;
@@ -19,15 +19,25 @@ fn pts(input: &[u64], days: usize) -> u64 {
     buffer.iter().sum()
 }
 
+/// The puzzle simulates 80 days.
+pub fn part1(input: &[u64], days: usize) -> u64 {
+    pts(input, days)
+}
+
+/// The puzzle simulates 256 days.
+pub fn part2(input: &[u64], days: usize) -> u64 {
+    pts(input, days)
+}
+
 fn pt1(input: &[u64]) -> u64 {
-    pts(input, 80)
+    part1(input, param("pt1_days"))
 }
 
 fn pt2(input: &[u64]) -> u64 {
-    pts(input, 256)
+    part2(input, param("pt2_days"))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<u64>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<u64>> {
     use parsers::*;
     number::<u64>().sep_by(token(b',')).parse(input)
 }
@@ -36,5 +46,6 @@ tests! {
     const EXAMPLE: &'static [u8] = b"3,4,3,1,2";
 
     simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 5934);
+    simple_tests!(parse, pt1, pt1_short_tests, params(pt1_days = 18), EXAMPLE => 26);
     simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 26984457539);
 }
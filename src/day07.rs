@@ -1,8 +1,8 @@
 use crate::prelude::*;
 
-day!(7, parse => pt1, pt2);
+day!(7, parse => part1, part2);
 
-fn pt1(input: &[u32]) -> SubmissionContext<u32, u32> {
+pub fn part1(input: &[u32]) -> SubmissionContext<u32, u32> {
     let input = {
         let mut v = input.to_owned();
         v.sort_unstable();
@@ -25,7 +25,7 @@ fn pt1(input: &[u32]) -> SubmissionContext<u32, u32> {
     }
 }
 
-fn pt2(input: &[u32]) -> SubmissionContext<u32, u32> {
+pub fn part2(input: &[u32]) -> SubmissionContext<u32, u32> {
     let (mut min, mut max) = input.iter().cloned().minmax().into_option().unwrap();
     max += 1;
     loop {
@@ -55,7 +55,7 @@ fn get_score(input: &[u32], position: u32) -> u32 {
         .sum()
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<u32>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<u32>> {
     use parsers::*;
     number::<u32>().sep_by(token(b',')).parse(input)
 }
@@ -63,6 +63,6 @@ fn parse(input: &[u8]) -> ParseResult<Vec<u32>> {
 tests! {
     const EXAMPLE: &'static [u8] = b"16,1,2,0,4,2,7,1,2,14";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => SubmissionContext(2, 37));
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => SubmissionContext(5, 168));
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => SubmissionContext(2, 37));
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => SubmissionContext(5, 168));
 }
@@ -1,17 +1,17 @@
 use crate::prelude::*;
 
-day!(8, parse => pt1, pt2);
+day!(8, parse => part1, part2);
 
 #[derive(Debug, Clone, Copy, Default, Hash)]
-struct Segments(u8);
+pub struct Segments(pub u8);
 
 #[derive(Debug, Clone)]
-struct Record {
-    measurements: [Segments; 10],
-    digits: [Segments; 4],
+pub struct Record {
+    pub measurements: [Segments; 10],
+    pub digits: [Segments; 4],
 }
 
-fn pt1(input: &[Record]) -> usize {
+pub fn part1(input: &[Record]) -> usize {
     input
         .iter()
         .flat_map(|x| x.digits.iter())
@@ -19,7 +19,7 @@ fn pt1(input: &[Record]) -> usize {
         .count()
 }
 
-fn pt2(input: &[Record]) -> u32 {
+pub fn part2(input: &[Record]) -> u32 {
     input.iter().map(get_record_value).sum()
 }
 
@@ -59,7 +59,7 @@ fn get_record_value(record: &Record) -> u32 {
         .fold(0u32, |res, nr| res * 10 + (nr as u32))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Record>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Record>> {
     use parsers::*;
     let segment_digit = #[rustfmt::skip] {
             token((b'a', 1_u8 << 0))
@@ -96,6 +96,6 @@ bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbg
 egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
 gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 26);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 61229);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 26);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 61229);
 }
@@ -2,7 +2,7 @@ use crate::prelude::*;
 use ahash::AHashSet;
 use arrayvec::ArrayVec;
 
-day!(9, parse => pt1, pt2);
+day!(9, parse => part1, part2);
 
 fn neighbors(p: Vec2u, input: &[Vec<u8>]) -> ArrayVec<Vec2u, 4> {
     let mut n = ArrayVec::new();
@@ -29,7 +29,7 @@ fn cells<'a>(input: &'a [Vec<u8>]) -> impl Iterator<Item = (Vec2u, u8)> + 'a {
     })
 }
 
-fn pt1(input: &[Vec<u8>]) -> u32 {
+pub fn part1(input: &[Vec<u8>]) -> u32 {
     cells(input)
         // low points
         .filter(|&(p, nr)| neighbors(p, input).iter().all(|&np| input[np.y][np.x] > nr))
@@ -38,7 +38,7 @@ fn pt1(input: &[Vec<u8>]) -> u32 {
         .sum()
 }
 
-fn pt2(input: &[Vec<u8>]) -> u32 {
+pub fn part2(input: &[Vec<u8>]) -> u32 {
     let mut visited = AHashSet::new();
     let mut basin_sizes = cells(input)
         .filter(|&(p, nr)| neighbors(p, input).iter().all(|&np| input[np.y][np.x] > nr))
@@ -67,7 +67,7 @@ fn get_basin_size(p: Vec2u, input: &[Vec<u8>], visited: &mut AHashSet<Vec2u>) ->
     size
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Vec<u8>>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Vec<u8>>> {
     use parsers::*;
     let input = input.strip_suffix(b"\n").unwrap_or(input);
     let row = digit().fold_mut(Vec::new(), |row, nr| row.push(nr));
@@ -82,6 +82,6 @@ tests! {
 8767896789
 9899965678";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 15);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 1134);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 15);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 1134);
 }
@@ -1,9 +1,9 @@
 use crate::prelude::*;
 
-day!(10, parse => pt1, pt2);
+day!(10, parse => part1, part2);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Delimiter {
+pub enum Delimiter {
     /* ( */ ParensOpen,
     /* ) */ ParensClose,
     /* [ */ BracketsOpen,
@@ -36,11 +36,11 @@ impl Delimiter {
 }
 
 #[derive(Debug, Clone)]
-struct Line {
-    delimiters: Vec<Delimiter>,
+pub struct Line {
+    pub delimiters: Vec<Delimiter>,
 }
 
-fn pt1(input: &[Line]) -> u32 {
+pub fn part1(input: &[Line]) -> u32 {
     let mut stack = Vec::new();
     input
         .iter()
@@ -73,7 +73,7 @@ fn get_corrupted_and_remaining(line: &Line, stack: &mut Vec<Delimiter>) -> Optio
     None
 }
 
-fn pt2(input: &[Line]) -> u64 {
+pub fn part2(input: &[Line]) -> u64 {
     let mut stack = Vec::new();
     let mut scores = input
         .iter()
@@ -104,7 +104,7 @@ fn pt2(input: &[Line]) -> u64 {
     scores[scores.len() / 2]
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Line>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Line>> {
     use parsers::*;
     let delimiter = #[rustfmt::skip] {
             token((b'(', Delimiter::ParensOpen))
@@ -134,6 +134,6 @@ tests! {
 <{([([[(<>()){}]>(<<{{
 <{([{{}}[<[[[<>{}]]]>[]]";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 26397);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 288957);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 26397);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 288957);
 }
@@ -1,10 +1,10 @@
 use crate::prelude::*;
 use arrayvec::ArrayVec;
 
-day!(11, parse => pt1, pt2);
+day!(11, parse => part1, part2);
 
 const FLASHED: u8 = 1 << 7;
-type Grid = Box<[[u8; 10]; 10]>;
+pub type Grid = Box<[[u8; 10]; 10]>;
 
 fn neighbors(p: Vec2u) -> ArrayVec<Vec2u, 8> {
     let mut n = ArrayVec::new();
@@ -49,9 +49,8 @@ fn step(flashed: &mut Vec<Vec2u>, grid: &mut Grid) -> usize {
     flash_count
 }
 
-fn pt1(input: &Grid) -> usize {
+pub fn part1(mut grid: Grid) -> usize {
     let mut flashed = Vec::new();
-    let mut grid = input.clone();
     let mut total_flashes = 0;
     for _ in 0..100 {
         total_flashes += step(&mut flashed, &mut grid);
@@ -60,9 +59,8 @@ fn pt1(input: &Grid) -> usize {
     total_flashes
 }
 
-fn pt2(input: &Grid) -> usize {
+pub fn part2(mut grid: Grid) -> usize {
     let mut flashed = Vec::new();
-    let mut grid = input.clone();
     for step_index in 1.. {
         if step(&mut flashed, &mut grid) == 100 {
             return step_index;
@@ -71,7 +69,7 @@ fn pt2(input: &Grid) -> usize {
     unreachable!();
 }
 
-fn parse(input: &[u8]) -> ParseResult<Grid> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Grid> {
     use parsers::{special::grid, *};
     grid(token(b'\n'), digit(), |x, y, v| Some((x, y, v))).parse(input)
 }
@@ -89,6 +87,6 @@ tests! {
 4846848554
 5283751526";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 1656);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 195);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 1656);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 195);
 }
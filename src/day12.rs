@@ -2,7 +2,7 @@ use crate::prelude::*;
 use jagged_array::{Jagged2, Jagged2Builder};
 use std::{cell::RefCell, mem::swap, ops::DerefMut};
 
-day!(12, parse => pt1, pt2);
+day!(12, parse => part1, part2);
 
 type Node = u8;
 const NODE_IS_LARGE: Node = 1 << 7;
@@ -13,7 +13,7 @@ type VisitedNodes = u32;
 const MAX_NODE_COUNT: usize = 32;
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     edges: Jagged2<Node>,
     _names: Vec<Vec<u8>>,
 }
@@ -22,7 +22,7 @@ fn get_row(edges: &Jagged2<Node>, node: Node) -> &[Node] {
     edges.get_row((node & !NODE_IS_LARGE) as usize).unwrap()
 }
 
-fn pt1(input: &Input) -> usize {
+pub fn part1(input: &Input) -> usize {
     fn visit(current_node: Node, input: &Input, visited: VisitedNodes, total_paths: &mut usize) {
         for &next_node in get_row(&input.edges, current_node) {
             if next_node == END_NODE {
@@ -41,7 +41,7 @@ fn pt1(input: &Input) -> usize {
     total_paths
 }
 
-fn pt2(input: &Input) -> usize {
+pub fn part2(input: &Input) -> usize {
     fn visit(
         current_node: Node,
         input: &Input,
@@ -86,7 +86,7 @@ fn get_edges<'s>(edges: &'s Vec<(Node, Node)>, node: Node) -> impl Iterator<Item
     })
 }
 
-fn parse(input: &[u8]) -> ParseResult<Input> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Input> {
     use parsers::*;
     let names = RefCell::new(vec![
         (START_NODE, b"start".to_vec()),
@@ -173,12 +173,12 @@ zg-he
 pj-fs
 start-RW";
 
-    simple_tests!(parse, pt1, pt1_tests,
+    simple_tests!(parse, part1, pt1_tests,
         EXAMPLE1 => 10,
         EXAMPLE2 => 19,
         EXAMPLE3 => 226,
     );
-    simple_tests!(parse, pt2, pt2_tests,
+    simple_tests!(parse, part2, pt2_tests,
         EXAMPLE1 => 36,
         EXAMPLE2 => 103,
         EXAMPLE3 => 3509,
@@ -2,18 +2,18 @@ use crate::prelude::*;
 use ahash::AHashSet;
 use std::mem::swap;
 
-day!(13, parse => pt1, pt2);
+day!(13, parse => part1, part2);
 
 #[derive(Debug, Clone)]
-struct Input {
-    points: Vec<Vec2u>,
-    folds: Vec<Fold>,
+pub struct Input {
+    pub points: Vec<Vec2u>,
+    pub folds: Vec<Fold>,
 }
 
-type Fold = (Direction, usize);
+pub type Fold = (Direction, usize);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     X,
     Y,
 }
@@ -65,13 +65,13 @@ fn paper_to_string(points: &AHashSet<Vec2u>) -> String {
     res
 }
 
-fn pt1(input: &Input) -> usize {
+pub fn part1(input: &Input) -> usize {
     let mut points = AHashSet::with_capacity(input.points.len());
     fold_paper(input.points.iter().cloned(), &mut points, &input.folds[0]);
     points.len()
 }
 
-fn pt2(input: &Input) -> String {
+pub fn part2(input: &Input) -> String {
     let mut points = AHashSet::from_iter(input.points.iter().cloned());
     let mut temp = AHashSet::with_capacity(input.points.len());
     for fold in &input.folds {
@@ -82,7 +82,7 @@ fn pt2(input: &Input) -> String {
         .unwrap_or_else(|| paper_to_string(&points))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Input> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Input> {
     use parsers::*;
     let point = number::<usize>().and(token(b',').then(number::<usize>()));
     let points = point.map(|(x, y)| Vec2u { x, y }).sep_by(token(b'\n'));
@@ -123,6 +123,6 @@ tests! {
 fold along y=7
 fold along x=5";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 17);
-    // simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 5);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 17);
+    // simple_tests!(parse, part2, pt2_tests, EXAMPLE => 5);
 }
@@ -2,39 +2,41 @@ use crate::prelude::*;
 use itertools::MinMaxResult;
 use std::{cell::RefCell, mem::swap};
 
-day!(14, parse => pt1, pt2);
+day!(14, parse => prep => pt1, pt2, params = &[("pt1_steps", 10), ("pt2_steps", 40)]);
 
-type Molecule = u8;
+pub type Molecule = u8;
 
 const MAX_MOLECULE_TYPE_COUNT: usize = 10;
 
 #[derive(Debug, Clone)]
-struct Input {
-    template: Vec<Molecule>,
-    rules: Vec<Rule>,
-    molecule_count: usize,
+pub struct Input {
+    pub template: Vec<Molecule>,
+    pub rules: Vec<Rule>,
+    pub molecule_count: usize,
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Rule {
-    lhs: Molecule,
-    rhs: Molecule,
-    add: Molecule,
+pub struct Rule {
+    pub lhs: Molecule,
+    pub rhs: Molecule,
+    pub add: Molecule,
 }
 
-fn pts(input: &Input, steps: usize) -> Result<SubSubmission<u64>> {
+/// The counts of every pair of molecules, followed by how often every molecule
+/// is counted twice, because it's part of two pairs.
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    input: Input,
+    counts: Vec<u64>,
+    steps: usize,
+}
+
+fn grow(input: &Input, counts: &mut Vec<u64>, steps: usize) {
     let overcounted_base = input.molecule_count * input.molecule_count;
-    let mut counts = vec![0u64; overcounted_base + input.molecule_count];
-    for &[a, b] in input.template.array_windows() {
-        counts[a as usize * input.molecule_count + b as usize] += 1;
-    }
-    for &x in &input.template[1..input.template.len() - 1] {
-        counts[overcounted_base + x as usize] += 1;
-    }
     let mut new_counts = counts.clone();
     for step_index in 0..steps {
         if step_index != 0 {
-            new_counts.copy_from_slice(&counts);
+            new_counts.copy_from_slice(counts);
         }
         for rule in &input.rules {
             let i1 = rule.lhs as usize * input.molecule_count + rule.rhs as usize;
@@ -46,9 +48,12 @@ fn pts(input: &Input, steps: usize) -> Result<SubSubmission<u64>> {
             new_counts[i3] += count;
             new_counts[overcounted_base + rule.add as usize] += count;
         }
-        swap(&mut counts, &mut new_counts);
+        swap(counts, &mut new_counts);
     }
+}
 
+fn score(input: &Input, counts: &[u64]) -> Result<SubSubmission<u64>> {
+    let overcounted_base = input.molecule_count * input.molecule_count;
     let mut total_counts = [0u64; MAX_MOLECULE_TYPE_COUNT];
     for a in 0..input.molecule_count {
         for b in 0..input.molecule_count {
@@ -72,15 +77,62 @@ fn pts(input: &Input, steps: usize) -> Result<SubSubmission<u64>> {
     }
 }
 
-fn pt1(input: &Input) -> Result<SubSubmission<u64>> {
-    pts(input, 10)
+fn template_counts(input: &Input) -> Vec<u64> {
+    let overcounted_base = input.molecule_count * input.molecule_count;
+    let mut counts = vec![0u64; overcounted_base + input.molecule_count];
+    for &[a, b] in input.template.array_windows() {
+        counts[a as usize * input.molecule_count + b as usize] += 1;
+    }
+    for &x in &input.template[1..input.template.len() - 1] {
+        counts[overcounted_base + x as usize] += 1;
+    }
+    counts
+}
+
+/// Grows the polymer for `steps` steps, from where the parts continue. Parts
+/// that take fewer steps start over from the template.
+pub fn prepare(input: Input, steps: usize) -> Polymer {
+    let mut counts = template_counts(&input);
+    grow(&input, &mut counts, steps);
+    Polymer {
+        input,
+        counts,
+        steps,
+    }
+}
+
+fn pts(polymer: &Polymer, steps: usize) -> Result<SubSubmission<u64>> {
+    let (mut counts, remaining) = match steps.checked_sub(polymer.steps) {
+        Some(remaining) => (polymer.counts.clone(), remaining),
+        None => (template_counts(&polymer.input), steps),
+    };
+    grow(&polymer.input, &mut counts, remaining);
+    score(&polymer.input, &counts)
+}
+
+/// The puzzle takes 10 steps.
+pub fn part1(polymer: &Polymer, steps: usize) -> Result<SubSubmission<u64>> {
+    pts(polymer, steps)
+}
+
+/// The puzzle takes 40 steps.
+pub fn part2(polymer: &Polymer, steps: usize) -> Result<u64> {
+    pts(polymer, steps).map(|SubSubmission(max, min)| max - min)
+}
+
+fn prep(input: Input) -> Polymer {
+    prepare(input, param::<usize>("pt1_steps").min(param("pt2_steps")))
+}
+
+fn pt1(polymer: &Polymer) -> Result<SubSubmission<u64>> {
+    part1(polymer, param("pt1_steps"))
 }
 
-fn pt2(input: &Input) -> Result<u64> {
-    pts(input, 40).map(|SubSubmission(max, min)| max - min)
+fn pt2(polymer: &Polymer) -> Result<u64> {
+    part2(polymer, param("pt2_steps"))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Input> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Input> {
     use parsers::*;
     let kind = RefCell::new(Vec::<(u8, u8)>::new());
     let molecule = any().map_res(|c| {
@@ -139,6 +191,6 @@ CC -> N
 CN -> C
 ";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => SubSubmission(1749, 161));
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 2188189693529);
+    simple_tests!(parse => prep, pt1, pt1_tests, EXAMPLE => SubSubmission(1749, 161));
+    simple_tests!(parse => prep, pt2, pt2_tests, EXAMPLE => 2188189693529);
 }
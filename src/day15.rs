@@ -1,20 +1,18 @@
 use crate::prelude::*;
 use framework::astar::astar_no_path;
 
-day!(15, parse::<100> => pt1::<100>, pt2::<100>);
+day!(15, parse => part1, pt2, generator = generate, params = &[("tiles", 5)]);
 
-type Grid<const N: usize> = Box<[[u8; N]; N]>;
+pub type Grid = parsers::special::DynGrid<u8>;
 
-type Cost = u32;
-fn pt1<const N: usize>(input: &Grid<N>) -> Cost
-where
-    [(); N * N]:,
-{
+pub type Cost = u32;
+fn lowest_total_risk(risks: &[u8], width: usize) -> Cost {
+    let height = risks.len() / width;
     astar_no_path(
         Vec2u::default(),
         |p: &Vec2u, neighbors| {
             let mut add_pos = |x: usize, y: usize| {
-                neighbors.push((Vec2u { x, y }, input[x][y] as Cost));
+                neighbors.push((Vec2u { x, y }, risks[y * width + x] as Cost));
             };
             if p.x != 0 {
                 add_pos(p.x - 1, p.y);
@@ -22,60 +20,61 @@ where
             if p.y != 0 {
                 add_pos(p.x, p.y - 1);
             }
-            if p.x != N - 1 {
+            if p.x != width - 1 {
                 add_pos(p.x + 1, p.y);
             }
-            if p.y != N - 1 {
+            if p.y != height - 1 {
                 add_pos(p.x, p.y + 1);
             }
         },
-        |p: &Vec2u| (p.x.abs_diff(N - 1) + p.y.abs_diff(N - 1)) as Cost,
-        |p: &Vec2u| p.x == N - 1 && p.y == N - 1,
+        |p: &Vec2u| (p.x.abs_diff(width - 1) + p.y.abs_diff(height - 1)) as Cost,
+        |p: &Vec2u| p.x == width - 1 && p.y == height - 1,
     )
     .unwrap()
 }
 
-fn pt2<const N: usize>(input: &Grid<N>) -> Cost
-where
-    [(); N * N]:,
-    [(); (N * 5) * (N * 5)]:,
-{
-    use parsers::special::GridSpec;
-    let mut full_grid = <Grid<{ N * 5 }> as GridSpec<u8>>::initialize();
-    for x in 0..N {
-        for y in 0..N {
-            full_grid[x][y] = input[x][y];
-        }
-    }
-    fn inc(n: u8) -> u8 {
-        if n == 9 {
-            1
-        } else {
-            n + 1
-        }
-    }
-    for x in 0..N {
-        for y in 0..N * 4 {
-            full_grid[x][y + N] = inc(full_grid[x][y]);
-        }
-    }
-    for x in 0..N * 4 {
-        for y in 0..N * 5 {
-            full_grid[x + N][y] = inc(full_grid[x][y]);
+pub fn part1(input: &Grid) -> Cost {
+    lowest_total_risk(&input.data, input.width)
+}
+
+/// The full map repeats the grid `tiles` times in both directions, 5 in the
+/// puzzle.
+pub fn part2(input: &Grid, tiles: usize) -> Cost {
+    let (width, height) = (input.width, input.data.len() / input.width);
+    let full_width = width * tiles;
+    let mut full_grid = vec![0; input.data.len() * tiles * tiles];
+    for y in 0..height * tiles {
+        for x in 0..full_width {
+            let increase = x / width + y / height;
+            let risk = input.data[(y % height) * width + x % width] as usize + increase;
+            full_grid[y * full_width + x] = ((risk - 1) % 9 + 1) as u8;
         }
     }
+    lowest_total_risk(&full_grid, full_width)
+}
 
-    pt1::<{ N * 5 }>(&full_grid)
+fn pt2(input: &Grid) -> Cost {
+    part2(input, param("tiles"))
 }
 
-fn parse<const N: usize>(input: &[u8]) -> ParseResult<Grid<N>>
-where
-    [(); N * N]:,
-{
+pub fn parse(input: &[u8]) -> ParseResult<'_, Grid> {
     use parsers::{special::grid, *};
     grid(token(b'\n'), digit(), |x, y, v| Some((x, y, v))).parse(input)
 }
 
+/// Generates a square grid of random risk levels, the real input is 100x100,
+/// and the amount of cells grows linearly with the scale.
+fn generate(seed: u64, scale: u32) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let size = (100.0 * (scale as f64).sqrt()).round() as usize;
+    let mut input = Vec::with_capacity((size + 1) * size);
+    for _ in 0..size {
+        input.extend((0..size).map(|_| b'1' + rng.below(9) as u8));
+        input.push(b'\n');
+    }
+    input
+}
+
 tests! {
     const EXAMPLE: &'static [u8] = b"\
 1163751742
@@ -89,6 +88,9 @@ tests! {
 1293138521
 2311944581";
 
-    simple_tests!(parse::<10>, pt1::<10>, pt1_tests, EXAMPLE => 40);
-    simple_tests!(parse::<10>, pt2::<10>, pt2_tests, EXAMPLE => 315);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 40);
+    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 315);
+    simple_tests!(parse, pt2, pt2_single_tile_tests, params(tiles = 1), EXAMPLE => 40);
+    simple_tests!(parse, pt2, pt2_many_tiles_tests, params(tiles = 200), b"1" => 1985);
+    generator_tests!(generate, parse);
 }
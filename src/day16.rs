@@ -1,13 +1,12 @@
 use crate::prelude::*;
-use bitvec::{prelude::*, view::BitView};
+use bitvec::prelude::*;
 use smallvec::SmallVec;
 
-day!(16, parse => pt1, pt2);
+day!(16, parse => part1, part2);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
-#[allow(dead_code)]
-enum PacketType {
+pub enum PacketType {
     Sum = 0,
     Product = 1,
     Minimum = 2,
@@ -18,123 +17,158 @@ enum PacketType {
     EqualTo = 7,
 }
 
-fn read_bit(remainder: &mut &BitSlice) -> bool {
-    let value = remainder[0];
-    *remainder = &remainder[1..];
-    value
+/// A decoded packet, see [`decode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub version: u8,
+    pub contents: Contents,
 }
 
-fn read_bits<T, const N: usize>(remainder: &mut &BitSlice) -> T
-where
-    T: Default + BitView + std::ops::ShrAssign<usize>,
-{
-    let mut value = T::default();
-    value.view_bits_mut::<Msb0>()[std::mem::size_of::<T>() * 8 - N..]
-        .clone_from_bitslice(&remainder[0..N]);
-    *remainder = &remainder[N..];
-    value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Contents {
+    Literal(u64),
+    /// An operator other than [`PacketType::Literal`], and its sub-packets.
+    Operator(PacketType, Vec<Packet>),
 }
 
-fn read_version(remainder: &mut &BitSlice) -> u8 {
-    read_bits::<u8, 3>(remainder)
+impl Packet {
+    /// The sum of the versions of this packet and all its sub-packets.
+    pub fn version_sum(&self) -> u64 {
+        let sub_packets = match &self.contents {
+            Contents::Literal(_) => &[][..],
+            Contents::Operator(_, sub_packets) => sub_packets,
+        };
+        sub_packets.iter().fold(self.version as u64, |sum, packet| {
+            sum + packet.version_sum()
+        })
+    }
+
+    /// Evaluates the expression of this packet.
+    pub fn value(&self) -> u64 {
+        let (packet_type, sub_packets) = match &self.contents {
+            Contents::Literal(value) => return *value,
+            Contents::Operator(packet_type, sub_packets) => (*packet_type, sub_packets),
+        };
+        let values = sub_packets
+            .iter()
+            .map(Packet::value)
+            .collect::<SmallVec<[u64; 8]>>();
+
+        // `decode` checks that there are enough sub-packets.
+        #[rustfmt::skip]
+        match packet_type {
+            PacketType::Sum     => values.iter().sum(),
+            PacketType::Product => values.iter().product(),
+            PacketType::Minimum => values.iter().cloned().min().unwrap(),
+            PacketType::Maximum => values.iter().cloned().max().unwrap(),
+            PacketType::Literal => unreachable!(),
+            PacketType::GreaterThan => if values[0] >  values[1] { 1 } else { 0 },
+            PacketType::LessThan    => if values[0] <  values[1] { 1 } else { 0 },
+            PacketType::EqualTo     => if values[0] == values[1] { 1 } else { 0 },
+        }
+    }
+}
+
+fn read_bits<'a>(remainder: &mut &'a BitSlice, n: usize) -> Result<&'a BitSlice> {
+    if remainder.len() < n {
+        return Err(anyhow!("the transmission ends in the middle of a packet"));
+    }
+    let (bits, rest) = remainder.split_at(n);
+    *remainder = rest;
+    Ok(bits)
 }
-fn read_packet_type(remainder: &mut &BitSlice) -> PacketType {
-    unsafe { std::mem::transmute(read_bits::<u8, 3>(remainder)) }
+
+fn read_bit(remainder: &mut &BitSlice) -> Result<bool> {
+    Ok(read_bits(remainder, 1)?[0])
+}
+
+fn read_int(remainder: &mut &BitSlice, n: usize) -> Result<u64> {
+    let bits = read_bits(remainder, n)?;
+    Ok(bits.iter().fold(0, |value, bit| value << 1 | *bit as u64))
 }
-fn read_5bit_encoded_int(remainder: &mut &BitSlice) -> u64 {
+
+fn read_packet_type(remainder: &mut &BitSlice) -> Result<PacketType> {
+    let packet_type = read_int(remainder, 3)? as u8;
+    Ok(unsafe { std::mem::transmute::<u8, PacketType>(packet_type) })
+}
+
+fn read_5bit_encoded_int(remainder: &mut &BitSlice) -> Result<u64> {
     let mut value = 0u64;
     loop {
-        let has_more = read_bit(remainder);
-        value = (value << 4) | (read_bits::<u8, 4>(remainder) as u64);
+        let has_more = read_bit(remainder)?;
+        if value >> 60 != 0 {
+            return Err(anyhow!("literal doesn't fit in 64 bits"));
+        }
+        value = (value << 4) | read_int(remainder, 4)?;
         if !has_more {
             break;
         }
     }
-    value
+    Ok(value)
 }
 
-fn pt1(input: &BitSlice) -> u64 {
-    fn read_packet(remainder: &mut &BitSlice, version_sum: &mut u64) {
-        let version = read_version(remainder);
-        *version_sum += version as u64;
-        let packet_type = read_packet_type(remainder);
+fn read_packet(remainder: &mut &BitSlice) -> Result<Packet> {
+    let version = read_int(remainder, 3)? as u8;
+    let packet_type = read_packet_type(remainder)?;
 
-        if packet_type == PacketType::Literal {
-            let _value = read_5bit_encoded_int(remainder);
-            return;
-        }
-
-        if !read_bit(remainder) {
-            // Operator packet with total length in bits
-            let bit_length = read_bits::<u16, 15>(remainder) as usize;
-            let expected_remainder = &remainder[bit_length..];
-            while expected_remainder != *remainder {
-                read_packet(remainder, version_sum);
-            }
-        } else {
-            // Operator packet with sub-packet count
-            let sub_packet_count = read_bits::<u16, 11>(remainder) as usize;
-            for _ in 0..sub_packet_count {
-                read_packet(remainder, version_sum);
-            }
-        }
+    if packet_type == PacketType::Literal {
+        let contents = Contents::Literal(read_5bit_encoded_int(remainder)?);
+        return Ok(Packet { version, contents });
     }
 
-    let mut version_sum = 0;
-    let mut remainder = input;
-    read_packet(&mut remainder, &mut version_sum);
-    assert!(remainder.count_ones() == 0);
+    let mut sub_packets = Vec::new();
 
-    version_sum
-}
-
-fn pt2(input: &BitSlice) -> u64 {
-    fn evaluate_packet(remainder: &mut &BitSlice) -> u64 {
-        let _version = read_version(remainder);
-        let packet_type = read_packet_type(remainder);
-
-        if packet_type == PacketType::Literal {
-            return read_5bit_encoded_int(remainder);
+    if !read_bit(remainder)? {
+        // Operator packet with total length in bits
+        let bit_length = read_int(remainder, 15)? as usize;
+        let mut sub_packet_bits = read_bits(remainder, bit_length)?;
+        while !sub_packet_bits.is_empty() {
+            sub_packets.push(read_packet(&mut sub_packet_bits)?);
         }
-
-        let mut sub_packets = SmallVec::<[u64; 8]>::new();
-
-        if !read_bit(remainder) {
-            // Operator packet with total length in bits
-            let bit_length = read_bits::<u16, 15>(remainder) as usize;
-            let expected_remainder = &remainder[bit_length..];
-            while expected_remainder != *remainder {
-                sub_packets.push(evaluate_packet(remainder));
-            }
-        } else {
-            // Operator packet with sub-packet count
-            let sub_packet_count = read_bits::<u16, 11>(remainder) as usize;
-            for _ in 0..sub_packet_count {
-                sub_packets.push(evaluate_packet(remainder));
-            }
+    } else {
+        // Operator packet with sub-packet count
+        let sub_packet_count = read_int(remainder, 11)?;
+        for _ in 0..sub_packet_count {
+            sub_packets.push(read_packet(remainder)?);
         }
+    }
 
-        #[rustfmt::skip]
-        match packet_type {
-            PacketType::Sum     => sub_packets.iter().cloned().sum(),
-            PacketType::Product => sub_packets.iter().cloned().product(),
-            PacketType::Minimum => sub_packets.iter().cloned().min().unwrap(),
-            PacketType::Maximum => sub_packets.iter().cloned().max().unwrap(),
-            PacketType::Literal => unreachable!(),
-            PacketType::GreaterThan => if sub_packets[0] >  sub_packets[1] { 1 } else { 0 },
-            PacketType::LessThan    => if sub_packets[0] <  sub_packets[1] { 1 } else { 0 },
-            PacketType::EqualTo     => if sub_packets[0] == sub_packets[1] { 1 } else { 0 },
+    match packet_type {
+        PacketType::GreaterThan | PacketType::LessThan | PacketType::EqualTo
+            if sub_packets.len() != 2 =>
+        {
+            Err(anyhow!(
+                "{packet_type:?} needs 2 sub-packets, not {}",
+                sub_packets.len()
+            ))
+        }
+        _ if sub_packets.is_empty() => Err(anyhow!("{packet_type:?} has no sub-packets")),
+        _ => {
+            let contents = Contents::Operator(packet_type, sub_packets);
+            Ok(Packet { version, contents })
         }
     }
+}
 
+/// Decodes the outermost packet of the transmission, with all its sub-packets.
+pub fn decode(input: &BitSlice) -> Result<Packet> {
     let mut remainder = input;
-    let result = evaluate_packet(&mut remainder);
-    assert!(remainder.count_ones() == 0);
+    let packet = read_packet(&mut remainder)?;
+    if remainder.any() {
+        return Err(anyhow!("the transmission continues after the packet"));
+    }
+    Ok(packet)
+}
+
+pub fn part1(input: &BitSlice) -> Result<u64> {
+    decode(input).map(|packet| packet.version_sum())
+}
 
-    result
+pub fn part2(input: &BitSlice) -> Result<u64> {
+    decode(input).map(|packet| packet.value())
 }
 
-fn parse(input: &[u8]) -> ParseResult<BitVec> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, BitVec> {
     use parsers::*;
     any()
         .map_res(|c| c.to_hex_digit().ok_or(ParseError::TokenDoesNotMatch))
@@ -145,7 +179,7 @@ fn parse(input: &[u8]) -> ParseResult<BitVec> {
 }
 
 tests! {
-    simple_tests!(parse, pt1, pt1_tests,
+    simple_tests!(parse, part1, pt1_tests,
         b"D2FE28" => 6,
         b"38006F45291200" => 1 + 6 + 2,
         b"8A004A801A8002F478" => 16,
@@ -153,7 +187,7 @@ tests! {
         b"C0015000016115A2E0802F182340" => 23,
         b"A0016C880162017C3686B18A3D4780" => 31,
     );
-    simple_tests!(parse, pt2, pt2_tests,
+    simple_tests!(parse, part2, pt2_tests,
         b"C200B40A82" => 3,
         b"04005AC33890" => 54,
         b"880086C3E88112" => 7,
@@ -1,9 +1,9 @@
 use crate::prelude::*;
 
-day!(17, parse => pt1, pt2);
+day!(17, parse => part1, part2);
 
-type Int = i32;
-type Range = std::ops::Range<Int>;
+pub type Int = i32;
+pub type Range = std::ops::Range<Int>;
 
 // Experimentally, for my input, this is actually 64, but I've opted to
 // make it 128 just to have a safety margin... Ideally I'd find a way to
@@ -11,9 +11,9 @@ type Range = std::ops::Range<Int>;
 const MAX_MISSES_IN_A_ROW: usize = 128;
 
 #[derive(Debug, Clone)]
-struct Area {
-    xs: Range,
-    ys: Range,
+pub struct Area {
+    pub xs: Range,
+    pub ys: Range,
 }
 
 #[derive(Debug, Clone)]
@@ -131,7 +131,7 @@ fn is_overlapping(x_range: &XRange, y_range: &YRange) -> bool {
     }
 }
 
-fn pt1(area: &Area) -> Int {
+pub fn part1(area: &Area) -> Int {
     let (x_ranges, y_ranges) = get_valid_ranges(area);
     let max_y_velocity = y_ranges
         .iter()
@@ -148,7 +148,7 @@ fn pt1(area: &Area) -> Int {
     sum_all_numbers_up_to(max_y_velocity)
 }
 
-fn pt2(area: &Area) -> usize {
+pub fn part2(area: &Area) -> usize {
     let (x_ranges, y_ranges) = get_valid_ranges(area);
     x_ranges
         .iter()
@@ -161,7 +161,7 @@ fn pt2(area: &Area) -> usize {
         .sum::<usize>()
 }
 
-fn parse(input: &[u8]) -> ParseResult<Area> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Area> {
     use parsers::*;
     let range = number::<i32>()
         .trailed(token(b".."))
@@ -178,6 +178,6 @@ fn parse(input: &[u8]) -> ParseResult<Area> {
 tests! {
     const EXAMPLE: &'static [u8] = b"target area: x=20..30, y=-10..-5";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 45);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 112);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 45);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 112);
 }
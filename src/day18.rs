@@ -5,7 +5,7 @@ use std::{
 
 use crate::prelude::*;
 
-day!(18, parse => pt1, pt2);
+day!(18, parse => part1, part2);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Part {
@@ -16,7 +16,7 @@ enum Part {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-struct SnailfishNr {
+pub struct SnailfishNr {
     parts: Vec<Part>,
 }
 
@@ -35,7 +35,8 @@ impl Display for SnailfishNr {
 }
 
 impl SnailfishNr {
-    fn add_with(&mut self, rhs: &Self) {
+    /// Appends `rhs`, without reducing the sum.
+    pub fn add_with(&mut self, rhs: &Self) {
         self.parts.reserve(rhs.parts.len() + 3);
         self.parts.insert(0, Part::OpenBracket);
         self.parts.push(Part::Separator);
@@ -121,7 +122,8 @@ impl SnailfishNr {
         false
     }
 
-    fn reduce(&mut self) {
+    /// Explodes and splits until neither applies anymore.
+    pub fn reduce(&mut self) {
         loop {
             if self.try_explode() {
                 continue;
@@ -133,7 +135,7 @@ impl SnailfishNr {
         }
     }
 
-    fn magnitude(&self) -> u64 {
+    pub fn magnitude(&self) -> u64 {
         fn pop_and_get(iter: &mut std::slice::Iter<Part>) -> u64 {
             match iter.next() {
                 Some(Part::OpenBracket) => {
@@ -151,7 +153,7 @@ impl SnailfishNr {
     }
 }
 
-fn pt1(input: &[SnailfishNr]) -> u64 {
+pub fn part1(input: &[SnailfishNr]) -> u64 {
     let mut nr = input[0].clone();
     for next in &input[1..] {
         nr.add_with(next);
@@ -160,7 +162,7 @@ fn pt1(input: &[SnailfishNr]) -> u64 {
     nr.magnitude()
 }
 
-fn pt2(input: &[SnailfishNr]) -> u64 {
+pub fn part2(input: &[SnailfishNr]) -> u64 {
     let mut temp = SnailfishNr::default();
     input
         .iter()
@@ -186,7 +188,7 @@ fn pt2(input: &[SnailfishNr]) -> u64 {
         .unwrap()
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<SnailfishNr>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<SnailfishNr>> {
     use parsers::*;
     let part = token((b'[', Part::OpenBracket))
         .or(token((b',', Part::Separator)))
@@ -221,7 +223,7 @@ tests! {
     simple_tests!(parse, reduce_nr, reduce_tests,
             b"[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]" => "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
     );
-    simple_tests!(parse, pt1, pt1_tests,
+    simple_tests!(parse, part1, pt1_tests,
         b"[[1,2],[[3,4],5]]" => 143,
         b"[[[[0,7],4],[[7,8],[6,0]]],[8,1]]" => 1384,
         b"[[[[1,1],[2,2]],[3,3]],[4,4]]" => 445,
@@ -230,5 +232,5 @@ tests! {
         b"[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]" => 3488,
         EXAMPLE => 4140,
     );
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 3993);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 3993);
 }
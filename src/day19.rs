@@ -2,11 +2,11 @@ use crate::prelude::*;
 use ahash::AHashSet;
 use std::{fmt::Display, ops::Mul};
 
-day!(19, parse => pt1, pt2);
+day!(19, parse => prepare => part1, part2);
 
-type Int = i32;
-type Vec3 = framework::vec::Vec3<Int>;
-type ScanData = Vec<Vec3>;
+pub type Int = i32;
+pub type Vec3 = framework::vec::Vec3<Int>;
+pub type ScanData = Vec<Vec3>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Matrix([i8; 9]);
@@ -179,34 +179,15 @@ fn determine_beacon_position(
     None
 }
 
-fn pt1(input: &[ScanData]) -> usize {
-    assert!(input.len() <= 64);
-    let mut all_points = AHashSet::<Vec3>::from_iter(input[0].iter().cloned());
-    let goal_mask = (1u64 << (input.len() - 1)) - 1;
-    let mut visited_mask = 0;
-    let mut buffer = Vec::new();
-    while goal_mask != visited_mask {
-        for (index, scan_data) in input
-            .iter()
-            .enumerate()
-            .skip(visited_mask.trailing_ones() as usize + 1)
-        {
-            let current_mask = 1 << (index - 1);
-            if (visited_mask & current_mask) == current_mask {
-                continue;
-            }
-            if determine_beacon_position(&all_points, scan_data, &mut buffer).is_some() {
-                all_points.extend(buffer.drain(..));
-                visited_mask |= current_mask;
-            }
-        }
-    }
-
-    all_points.len()
+/// The beacons and scanners, all relative to the first scanner.
+#[derive(Debug, Clone)]
+pub struct Map {
+    pub beacons: AHashSet<Vec3>,
+    pub scanners: Vec<Vec3>,
 }
 
-fn pt2(input: &[ScanData]) -> Int {
-    let mut all_points = AHashSet::<Vec3>::from_iter(input[0].iter().cloned());
+pub fn prepare(input: Vec<ScanData>) -> Map {
+    let mut beacons = AHashSet::<Vec3>::from_iter(input[0].iter().cloned());
     let mut positions = vec![None; input.len()];
     positions[0] = Some(Vec3::default());
     let mut scanners_left = input.len() - 1;
@@ -216,26 +197,37 @@ fn pt2(input: &[ScanData]) -> Int {
             if positions[index].is_some() {
                 continue;
             }
-            if let Some(position) = determine_beacon_position(&all_points, scan_data, &mut buffer) {
-                all_points.extend(buffer.drain(..));
+            if let Some(position) = determine_beacon_position(&beacons, scan_data, &mut buffer) {
+                beacons.extend(buffer.drain(..));
                 positions[index] = Some(position);
                 scanners_left -= 1;
             }
         }
     }
 
-    let positions = positions.as_slice();
-    (0..positions.len())
+    Map {
+        beacons,
+        scanners: positions.into_iter().map(Option::unwrap).collect(),
+    }
+}
+
+pub fn part1(map: &Map) -> usize {
+    map.beacons.len()
+}
+
+pub fn part2(map: &Map) -> Int {
+    let scanners = map.scanners.as_slice();
+    (0..scanners.len())
         .flat_map(|i| {
-            (0..positions.len())
+            (0..scanners.len())
                 .filter(move |&j| i != j)
-                .map(move |j| positions[i].unwrap().manhathan_dist(positions[j].unwrap()))
+                .map(move |j| scanners[i].manhathan_dist(scanners[j]))
         })
         .max()
         .unwrap()
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<ScanData>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<ScanData>> {
     use parsers::*;
     let header = token(b"--- scanner ")
         .then(number::<usize>())
@@ -391,6 +383,6 @@ tests! {
 -652,-548,-490
 30,-46,-14";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 79);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 3621);
+    simple_tests!(parse => prepare, part1, pt1_tests, EXAMPLE => 79);
+    simple_tests!(parse => prepare, part2, pt2_tests, EXAMPLE => 3621);
 }
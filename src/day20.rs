@@ -2,10 +2,10 @@ use crate::prelude::*;
 use bitvec::prelude::*;
 use std::{fmt::Display, mem::swap};
 
-day!(20, parse => pt1, pt2);
+day!(20, parse => prep => pt1, pt2, params = &[("pt1_steps", 2), ("pt2_steps", 50)]);
 
 #[derive(Debug, Clone)]
-struct Input {
+pub struct Input {
     pattern: BitVec,
     image: Image,
 }
@@ -97,26 +97,72 @@ fn enhance_image(pattern: &BitSlice, input: &Image, output: &mut Image) {
 //     });
 // }
 
-fn enhance_n_times(input: &Input, n: usize) -> usize {
-    let mut current_image = input.image.clone();
+/// The image after the enhancements that both parts share, from where they
+/// continue.
+#[derive(Debug, Clone)]
+pub struct Enhanced {
+    input: Input,
+    image: Image,
+    steps: usize,
+}
+
+fn enhance_n_times(pattern: &BitSlice, image: &Image, n: usize) -> Image {
+    let mut current_image = image.clone();
     let mut new_image = current_image.clone();
     for _ in 0..n {
-        enhance_image(&input.pattern, &current_image, &mut new_image);
+        enhance_image(pattern, &current_image, &mut new_image);
         swap(&mut current_image, &mut new_image);
     }
+    current_image
+}
+
+/// Enhances the image `steps` times, from where the parts continue. Parts that
+/// enhance it fewer times start over from the input image.
+pub fn prepare(input: Input, steps: usize) -> Enhanced {
+    let image = enhance_n_times(&input.pattern, &input.image, steps);
+    Enhanced {
+        input,
+        image,
+        steps,
+    }
+}
+
+fn pts(enhanced: &Enhanced, steps: usize) -> usize {
+    let (image, remaining) = match steps.checked_sub(enhanced.steps) {
+        Some(remaining) => (&enhanced.image, remaining),
+        None => (&enhanced.input.image, steps),
+    };
+    if remaining == 0 {
+        return image.data.count_ones();
+    }
+    enhance_n_times(&enhanced.input.pattern, image, remaining)
+        .data
+        .count_ones()
+}
+
+/// The puzzle enhances the image twice.
+pub fn part1(enhanced: &Enhanced, steps: usize) -> usize {
+    pts(enhanced, steps)
+}
+
+/// The puzzle enhances the image 50 times.
+pub fn part2(enhanced: &Enhanced, steps: usize) -> usize {
+    pts(enhanced, steps)
+}
 
-    current_image.data.count_ones()
+fn prep(input: Input) -> Enhanced {
+    prepare(input, param::<usize>("pt1_steps").min(param("pt2_steps")))
 }
 
-fn pt1(input: &Input) -> usize {
-    enhance_n_times(input, 2)
+fn pt1(enhanced: &Enhanced) -> usize {
+    part1(enhanced, param("pt1_steps"))
 }
 
-fn pt2(input: &Input) -> usize {
-    enhance_n_times(input, 50)
+fn pt2(enhanced: &Enhanced) -> usize {
+    part2(enhanced, param("pt2_steps"))
 }
 
-fn parse(input: &[u8]) -> ParseResult<Input> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Input> {
     use parsers::{special::grid, *};
     let bit = token((b'.', false)).or(token((b'#', true)));
     let pattern = bit.clone().repeat_into();
@@ -152,6 +198,7 @@ tests! {
 ..#..
 ..###";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 35);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 3351);
+    simple_tests!(parse => prep, pt1, pt1_tests, EXAMPLE => 35);
+    simple_tests!(parse => prep, pt2, pt2_tests, EXAMPLE => 3351);
+    simple_tests!(parse => prep, pt2, pt2_short_tests, params(pt2_steps = 1), EXAMPLE => 24);
 }
@@ -1,7 +1,7 @@
 use crate::prelude::*;
 use std::{iter::Cycle, ops::Range};
 
-day!(21, parse => pt1, pt2);
+day!(21, parse => part1, part2);
 
 struct Game {
     dice: Cycle<Range<u32>>,
@@ -65,7 +65,7 @@ impl Player {
     }
 }
 
-fn pt1(input: &(u8, u8)) -> MulSubmission<u32> {
+pub fn part1(input: &(u8, u8)) -> MulSubmission<u32> {
     let mut game = Game::new(input);
     game.play();
     MulSubmission(game.p1.score.min(game.p2.score), game.rolls)
@@ -74,7 +74,7 @@ fn pt1(input: &(u8, u8)) -> MulSubmission<u32> {
 const ROLL_SUM_AND_MULTIPLIER: [(u8, u8); 7] =
     [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
 
-fn pt2(input: &(u8, u8)) -> u64 {
+pub fn part2(input: &(u8, u8)) -> u64 {
     // Positions are 1 less than the "rules" say
     fn play_and_count_victories(
         active_pos: u8,
@@ -103,7 +103,7 @@ fn pt2(input: &(u8, u8)) -> u64 {
     p1_wins.max(p2_wins)
 }
 
-fn parse(input: &[u8]) -> ParseResult<(u8, u8)> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, (u8, u8)> {
     use parsers::*;
     token(b"Player 1 starting position: ")
         .then(number::<u8>())
@@ -117,6 +117,6 @@ tests! {
 Player 1 starting position: 4
 Player 2 starting position: 8";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => MulSubmission(745, 993));
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 444356092776315);
-}
\ No newline at end of file
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => MulSubmission(745, 993));
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 444356092776315);
+}
@@ -1,26 +1,28 @@
 use crate::prelude::*;
 use arrayvec::ArrayVec;
+use std::{fmt::Write, ops::Range};
 
-day!(22, parse => pt1, pt2);
+day!(22, parse => part1, part2, generator = generate);
 
-type Int = i32;
-type Vec3<T = Int> = framework::vec::Vec3<T>;
+pub type Int = i32;
+pub type Vec3<T = Int> = framework::vec::Vec3<T>;
 
 #[derive(Debug, Clone)]
-struct Instruction {
-    turn_on: bool,
-    region: Region,
+pub struct Instruction {
+    pub turn_on: bool,
+    pub region: Region,
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Region {
-    min: Vec3,
+pub struct Region {
+    pub min: Vec3,
     // exclusive upper bound
-    max: Vec3,
+    pub max: Vec3,
 }
 
 impl Region {
-    fn overlaps(&self, other: &Region) -> bool {
+    /// Whether the two regions share at least one cube.
+    pub fn overlaps(&self, other: &Region) -> bool {
         fn one_way(a: &Region, b: &Region) -> bool {
             a.min.x < b.max.x && a.min.y < b.max.y && a.min.z < b.max.z
         }
@@ -70,7 +72,8 @@ impl Region {
         new_regions
     }
 
-    fn size(&self) -> u64 {
+    /// The amount of cubes in the region.
+    pub fn size(&self) -> u64 {
         let d = self.max - self.min;
         d.x as u64 * d.y as u64 * d.z as u64
     }
@@ -120,7 +123,7 @@ where
     active_regions.iter().map(Region::size).sum()
 }
 
-fn pt1(input: &[Instruction]) -> u64 {
+pub fn part1(input: &[Instruction]) -> u64 {
     fn map(v: Int) -> Int {
         v.clamp(-50, 51)
     }
@@ -142,7 +145,7 @@ fn pt1(input: &[Instruction]) -> u64 {
     count_overlap_size(truncated_instructions)
 }
 
-fn pt2(input: &[Instruction]) -> u64 {
+pub fn part2(input: &[Instruction]) -> u64 {
     fn sort(regions: &mut [Instruction]) {
         regions.sort_unstable_by(|a, b| b.region.size().cmp(&a.region.size()));
     }
@@ -165,7 +168,7 @@ fn pt2(input: &[Instruction]) -> u64 {
     count_overlap_size(regions.iter().cloned())
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Instruction>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Instruction>> {
     use parsers::*;
     let turn_on = token((b"on", true)).or(token((b"off", false)));
     let range = number::<Int>()
@@ -197,6 +200,32 @@ fn parse(input: &[u8]) -> ParseResult<Vec<Instruction>> {
     instruction.sep_by(token(b'\n')).parse(input)
 }
 
+/// Generates instructions like the real input, which has 20 small cuboids
+/// around the origin for part 1, followed by 400 large ones. Only the amount
+/// of large cuboids grows with the scale.
+fn generate(seed: u64, scale: u32) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let mut cuboid = |rng: &mut Rng, extent: Range<i64>, bound: i64| {
+        let turn_on = if rng.chance(0.75) { "on" } else { "off" };
+        write!(input, "{turn_on}").unwrap();
+        for axis in ["x", "y", "z"] {
+            let size = rng.range(extent.clone());
+            let min = rng.range(-bound..bound - size);
+            let separator = if axis == "x" { ' ' } else { ',' };
+            write!(input, "{separator}{axis}={min}..{}", min + size).unwrap();
+        }
+        input.push('\n');
+    };
+    for _ in 0..20 {
+        cuboid(&mut rng, 10..50, 50);
+    }
+    for _ in 0..400 * scale {
+        cuboid(&mut rng, 1000..38000, 100000);
+    }
+    input.into_bytes()
+}
+
 tests! {
     const EXAMPLE1: &'static [u8] = b"\
 on x=10..12,y=10..12,z=10..12
@@ -288,13 +317,14 @@ off x=-70369..-16548,y=22648..78696,z=-1892..86821
 on x=-53470..21291,y=-120233..-33476,z=-44150..38147
 off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
 
-    simple_tests!(parse, pt1, pt1_tests,
+    simple_tests!(parse, part1, pt1_tests,
         EXAMPLE1 => 39,
         EXAMPLE2 => 590784,
         EXAMPLE3 => 474140,
     );
-    simple_tests!(parse, pt2, pt2_tests,
+    simple_tests!(parse, part2, pt2_tests,
         EXAMPLE1 => 39,
         EXAMPLE3 => 2758514936282235,
     );
+    generator_tests!(generate, parse);
 }
@@ -5,9 +5,9 @@ use std::{
     hash::Hash,
 };
 
-day!(23, parse => pt1, pt2);
+day!(23, parse => part1, part2);
 
-type Cost = u32;
+pub type Cost = u32;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -32,7 +32,7 @@ const COSTS: [Cost; 4] = [1, 10, 100, 1000];
 //   #1#3#5#7#   ^^^
 //   #########
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Positions<const DEPTH: usize>
+pub struct Positions<const DEPTH: usize>
 where
     [(); DEPTH * 4]:,
 {
@@ -203,11 +203,11 @@ where
     .ok_or(anyhow!("no solution"))
 }
 
-fn pt1(input: &Positions<2>) -> Result<Cost> {
-    run(*input)
+pub fn part1(input: Positions<2>) -> Result<Cost> {
+    run(input)
 }
 
-fn pt2(input: &Positions<2>) -> Result<Cost> {
+pub fn part2(input: Positions<2>) -> Result<Cost> {
     let positions = Positions::<4> {
         hallway: Default::default(),
         missing_counts: Default::default(),
@@ -233,7 +233,7 @@ fn pt2(input: &Positions<2>) -> Result<Cost> {
     run(positions)
 }
 
-fn parse(input: &[u8]) -> ParseResult<Positions<2>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Positions<2>> {
     use parsers::*;
     let amphi = #[rustfmt::skip]
             token((b'A', Some(Amphipod::Amber)))
@@ -283,6 +283,6 @@ tests! {
   #A#D#C#A#
   #########";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 12521);
-    simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 44169);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 12521);
+    simple_tests!(parse, part2, pt2_tests, EXAMPLE => 44169);
 }
@@ -1,13 +1,13 @@
 use crate::prelude::*;
 use std::fmt::Display;
 
-day!(24, parse => pt1, pt2);
+day!(24, parse => part1, part2);
 
-type Int = i64;
+pub type Int = i64;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
-enum Register {
+pub enum Register {
     W,
     X,
     Y,
@@ -16,7 +16,7 @@ enum Register {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
-enum OpCode {
+pub enum OpCode {
     Inp,
     Add,
     Mul,
@@ -26,17 +26,17 @@ enum OpCode {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Arg {
+pub enum Arg {
     Register(Register),
     Constant(Int),
     None,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Instruction {
-    opcode: OpCode,
-    a: Register,
-    b: Arg,
+pub struct Instruction {
+    pub opcode: OpCode,
+    pub a: Register,
+    pub b: Arg,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -227,15 +227,15 @@ fn pts<const REV: bool>(program: &[Instruction]) -> Result<Int> {
         .ok_or_else(|| anyhow!("no solution"))
 }
 
-fn pt1(program: &[Instruction]) -> Result<Int> {
+pub fn part1(program: &[Instruction]) -> Result<Int> {
     pts::<true>(program)
 }
 
-fn pt2(program: &[Instruction]) -> Result<Int> {
+pub fn part2(program: &[Instruction]) -> Result<Int> {
     pts::<false>(program)
 }
 
-fn parse(input: &[u8]) -> ParseResult<Vec<Instruction>> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, Vec<Instruction>> {
     use parsers::*;
     let register = any().map_res(|c| match c {
         b'w' => Ok(Register::W),
@@ -1,17 +1,17 @@
 use crate::prelude::*;
 
-day!(25, parse => pt1, pt2);
+day!(25, parse => part1, part2);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-enum Cell {
+pub enum Cell {
     #[default]
     Empty,
     Right,
     Down,
 }
-type DynGrid = parsers::special::DynGrid<Cell>;
+pub type DynGrid = parsers::special::DynGrid<Cell>;
 
-fn pt1(input: &DynGrid) -> usize {
+pub fn part1(input: &DynGrid) -> usize {
     let width = input.width;
     let height = input.data.len() / width;
     let mut current = input.data.clone();
@@ -59,11 +59,11 @@ fn pt1(input: &DynGrid) -> usize {
     }
 }
 
-fn pt2(_: &DynGrid) -> &'static str {
+pub fn part2(_: &DynGrid) -> &'static str {
     "gg"
 }
 
-fn parse(input: &[u8]) -> ParseResult<DynGrid> {
+pub fn parse(input: &[u8]) -> ParseResult<'_, DynGrid> {
     use parsers::{special::grid, *};
     let cell = any().map_res(|c| match c {
         b'.' => Ok(Cell::Empty),
@@ -86,6 +86,6 @@ v>v.vv.v..
 v.v..>>v.v
 ....v..v.>";
 
-    simple_tests!(parse, pt1, pt1_tests, EXAMPLE => 58);
-    // simple_tests!(parse, pt2, pt2_tests, EXAMPLE => 5);
+    simple_tests!(parse, part1, pt1_tests, EXAMPLE => 58);
+    // simple_tests!(parse, part2, pt2_tests, EXAMPLE => 5);
 }
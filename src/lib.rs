@@ -0,0 +1,20 @@
+//! The solutions of Advent of Code 2021. Every day is a module with a public
+//! `parse` function, `part1` and `part2`, and the types they use.
+
+#![allow(incomplete_features)]
+#![feature(
+    adt_const_params,
+    array_windows,
+    const_for,
+    const_trait_impl,
+    const_mut_refs,
+    generic_const_exprs,
+    never_type,
+    slice_group_by,
+    stmt_expr_attributes
+)]
+
+mod ocr;
+mod prelude;
+
+framework::days!();
@@ -1,43 +1 @@
-#![allow(incomplete_features)]
-#![feature(
-    adt_const_params,
-    array_windows,
-    const_for,
-    const_trait_impl,
-    const_mut_refs,
-    generic_const_exprs,
-    never_type,
-    slice_group_by,
-    stmt_expr_attributes
-)]
-
-mod prelude;
-mod ocr;
-
-framework::main!(
-    day01,
-    day02,
-    day03,
-    day04,
-    day05,
-    day06,
-    day07,
-    day08,
-    day09,
-    day10,
-    day11,
-    day12,
-    day13,
-    day14,
-    day15,
-    day16,
-    day17,
-    day18,
-    day19,
-    day20,
-    day21,
-    day22,
-    day23,
-    day24,
-    day25,
-);
+framework::main!(year = 2021, lib = advent_of_code_2021);
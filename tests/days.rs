@@ -0,0 +1,101 @@
+use advent_of_code_2021::*;
+use anyhow::Result;
+use framework::{day::ToAnswer, parsers::error::Finish, render};
+
+#[test]
+fn all_days() {
+    let nrs = days().iter().map(|day| day.nr()).collect::<Vec<_>>();
+    assert_eq!((1..=25).collect::<Vec<_>>(), nrs);
+    assert!(std::ptr::eq(days(), days()));
+}
+
+#[test]
+fn parse_and_solve() -> Result<()> {
+    let depths = day01::parse(b"199\n200\n208\n210\n200\n207\n240\n269\n260\n263").finish()?;
+    assert_eq!(7, day01::part1(&depths));
+    assert_eq!(5, day01::part2(&depths));
+
+    let transmission = day16::parse(b"9C0141080250320F1802104A08").finish()?;
+    assert_eq!(1, day16::part2(&transmission)?);
+    Ok(())
+}
+
+#[test]
+fn packets() -> Result<()> {
+    use day16::{Contents, Packet, PacketType};
+
+    let literal = |version, value| Packet {
+        version,
+        contents: Contents::Literal(value),
+    };
+    let transmission = day16::parse(b"D2FE28").finish()?;
+    assert_eq!(literal(6, 2021), day16::decode(&transmission)?);
+
+    let transmission = day16::parse(b"38006F45291200").finish()?;
+    let expected = Packet {
+        version: 1,
+        contents: Contents::Operator(PacketType::LessThan, vec![literal(6, 10), literal(2, 20)]),
+    };
+    assert_eq!(expected, day16::decode(&transmission)?);
+
+    let transmission = day16::parse(b"38006F452912").finish()?;
+    assert!(day16::decode(&transmission).is_err());
+    Ok(())
+}
+
+#[test]
+fn params() -> Result<()> {
+    let report = day03::parse(
+        b"00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010",
+    )
+    .finish()?;
+    assert_eq!("198", render::plain(&day03::part1(&report, 5).to_answer()));
+    Ok(())
+}
+
+#[test]
+fn fewer_steps_than_prepared() -> Result<()> {
+    let input = day14::parse(
+        b"NNCB\n\nCH -> B\nHH -> N\nCB -> H\nNH -> C\nHB -> C\nHC -> B\nHN -> C\nNN -> C\n\
+          BH -> H\nNC -> B\nNB -> B\nBN -> B\nBB -> N\nBC -> B\nCC -> N\nCN -> C\n",
+    )
+    .finish()?;
+    let answer = day14::part1(&day14::prepare(input, 40), 10)?;
+    assert_eq!("1588", render::plain(&answer.to_answer()));
+
+    let input = format!("{}\n\n#..#.\n#....\n##..#\n..#..\n..###", "#.".repeat(256));
+    let prepared = day20::prepare(day20::parse(input.as_bytes()).finish()?, 2);
+    let input = day20::parse(input.as_bytes()).finish()?;
+    assert_eq!(
+        day20::part1(&day20::prepare(input, 1), 1),
+        day20::part1(&prepared, 1)
+    );
+    Ok(())
+}
+
+#[test]
+fn snailfish_numbers() -> Result<()> {
+    let mut nrs = day18::parse(b"[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]").finish()?;
+    let rhs = nrs.pop().unwrap();
+    let mut sum = nrs.pop().unwrap();
+    sum.add_with(&rhs);
+    sum.reduce();
+    assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", sum.to_string());
+    assert_eq!(
+        1384,
+        day18::parse(b"[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").finish()?[0].magnitude()
+    );
+    Ok(())
+}
+
+#[test]
+fn regions() -> Result<()> {
+    let instructions =
+        day22::parse(b"on x=10..12,y=10..12,z=10..12\noff x=9..11,y=9..11,z=9..11").finish()?;
+    let (on, off) = (instructions[0].region, instructions[1].region);
+    assert!(instructions[0].turn_on && !instructions[1].turn_on);
+    assert_eq!(27, on.size());
+    assert!(on.overlaps(&off));
+    assert_eq!(19, day22::part1(&instructions));
+    Ok(())
+}